@@ -0,0 +1,4672 @@
+//! The code generation behind the `overloadable` crate's macros, for use in other
+//! procedural macros.
+//!
+//! Each of the macros has a function of the same name here, which takes the tokens
+//! within the invocation and returns its expansion, with any errors given as
+//! `compile_error!`s. Overloads can also be assembled without writing out the syntax,
+//! through an [`OverloadSet`]:
+//!
+//! ```
+//! use overloadable_codegen::{Overload, OverloadSet};
+//! use quote::quote;
+//! use syn::parse_quote;
+//!
+//! let set = OverloadSet::new(parse_quote!(double))
+//!     .vis(parse_quote!(pub))
+//!     .overload(Overload::new(vec![(parse_quote!(x), parse_quote!(u8))], parse_quote!(-> u8), parse_quote!({ x * 2 })))
+//!     .overload(Overload::new(vec![(parse_quote!(x), parse_quote!(&str))], parse_quote!(-> String), parse_quote!({ x.repeat(2) })));
+//! let expanded = quote!(#set);
+//! assert!(expanded.to_string().contains("pub struct double"));
+//! ```
+use std::collections::{HashMap, HashSet};
+use proc_macro2::{Delimiter, Span, TokenStream as Tok2, TokenTree};
+use quote::{quote, quote_spanned, ToTokens};
+use syn::{
+    braced,
+    parenthesized,
+    parse::{Parse, ParseStream, Parser, Result},
+    parse_quote,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    token::{Brace, Bracket, Paren},
+    visit_mut::{self, VisitMut},
+    Abi,
+    AngleBracketedGenericArguments,
+    ArgCaptured,
+    ArgSelf,
+    ArgSelfRef,
+    Attribute,
+    Binding,
+    Block,
+    BoundLifetimes,
+    ConstParam,
+    Error,
+    Expr,
+    Field,
+    FnArg,
+    GenericArgument,
+    GenericParam,
+    Generics,
+    Ident,
+    ImplItem,
+    Item,
+    Lifetime,
+    LifetimeDef,
+    Lit,
+    Meta,
+    MetaNameValue,
+    NestedMeta,
+    ParenthesizedGenericArguments,
+    Pat,
+    PatIdent,
+    PathArguments,
+    ReturnType,
+    Stmt,
+    Token,
+    Type,
+    TypeBareFn,
+    TypeImplTrait,
+    TypeParam,
+    TypePath,
+    TypeReference,
+    TypeTuple,
+    Visibility,
+    WhereClause,
+};
+
+/// One overload of an [`OverloadSet`], as it would be written in the macros.
+#[derive(Clone)]
+pub struct Overload {
+    /// Attributes of the overload, such as `#[inline]` or `#[cfg(...)]`.
+    pub attrs: Vec<Attribute>,
+    /// Generics of the overload, including its `where` clause.
+    pub generics: Generics,
+    /// The `self`-style parameter of a member overload.
+    pub receiver: Option<FnArg>,
+    /// The patterns and types of the other parameters, which tell overloads apart.
+    pub params: Vec<(Pat, Type)>,
+    /// The return type, which is `()` if left out.
+    pub output: ReturnType,
+    /// The body, in which `Self` is the generated struct, or the member's type.
+    pub body: Block,
+}
+
+impl Overload {
+    /// An overload without attributes, generics or a receiver.
+    pub fn new(params: Vec<(Pat, Type)>, output: ReturnType, body: Block) -> Self {
+        Self {
+            attrs: Vec::new(),
+            generics: Generics::default(),
+            receiver: None,
+            params,
+            output,
+            body,
+        }
+    }
+}
+
+/// Which macro an [`OverloadSet`] is expanded like.
+#[derive(Clone)]
+enum SetKind {
+    Global,
+    Stable,
+    Member(Ident),
+}
+
+/// A set of overloads to generate, like an invocation of `overloadable!`,
+/// `overloadable_stable!` or `overloadable_member!`, which expands to the same
+/// code through `ToTokens`.
+#[derive(Clone)]
+pub struct OverloadSet {
+    kind: SetKind,
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    name: Ident,
+    overloads: Vec<Overload>,
+}
+
+impl OverloadSet {
+    /// Overloads called through the `Fn*` traits, as with `overloadable!`.
+    pub fn new(name: Ident) -> Self {
+        Self::with_kind(SetKind::Global, name)
+    }
+
+    /// Overloads called through a `call` method, as with `overloadable_stable!`.
+    pub fn stable(name: Ident) -> Self {
+        Self::with_kind(SetKind::Stable, name)
+    }
+
+    /// Overloads declared on `struct_name`, as with `overloadable_member!`.
+    pub fn member(struct_name: Ident, name: Ident) -> Self {
+        Self::with_kind(SetKind::Member(struct_name), name)
+    }
+
+    fn with_kind(kind: SetKind, name: Ident) -> Self {
+        Self {
+            kind,
+            attrs: Vec::new(),
+            vis: Visibility::Inherited,
+            name,
+            overloads: Vec::new(),
+        }
+    }
+
+    /// The visibility of the generated struct, or of the member's traits.
+    pub fn vis(mut self, vis: Visibility) -> Self {
+        self.vis = vis;
+        self
+    }
+
+    /// Adds an attribute for the generated struct, which member overloads don't have.
+    pub fn attr(mut self, attr: Attribute) -> Self {
+        self.attrs.push(attr);
+        self
+    }
+
+    /// Adds an overload, after any added before it.
+    pub fn overload(mut self, overload: Overload) -> Self {
+        self.overloads.push(overload);
+        self
+    }
+
+    /// Expands the set, or returns the first overload which isn't valid as such, as
+    /// with a receiver on a global overload or an attribute which isn't a `Meta`.
+    /// Errors in the overloads as a whole are still given as `compile_error!`s.
+    pub fn expand(&self) -> Result<Tok2> {
+        let OverloadSet { kind, attrs, vis, name, overloads } = self.clone();
+        let member = matches!(kind, SetKind::Member(_));
+        let fns = overloads
+            .into_iter()
+            .map(|overload| ParsedFnDef::from_overload(overload, member))
+            .collect::<Result<Vec<_>>>()?;
+        let global = |fns: Vec<ParsedFnDef>| OverloadableGlobal {
+            attrs,
+            callable: None,
+            dynamic: None,
+            documented: None,
+            vis: vis.clone(),
+            name: name.clone(),
+            fields: None,
+            _as_keyword: Default::default(),
+            fns: fns.into_iter().collect(),
+        };
+        Ok(match kind {
+            SetKind::Global => gen_global_set(global(fns)),
+            SetKind::Stable => gen_stable_set(global(fns)),
+            SetKind::Member(struct_name) => gen_member_set(OverloadableAssociated {
+                grouped: None,
+                inherent: None,
+                vis,
+                struct_names: vec![struct_name],
+                struct_gen: Generics::default(),
+                _colons: Default::default(),
+                name,
+                _as: Default::default(),
+                items: fns.into_iter().map(MemberItem::Fn).collect(),
+            }),
+        })
+    }
+}
+
+impl ToTokens for OverloadSet {
+    fn to_tokens(&self, tokens: &mut Tok2) {
+        tokens.extend(self.expand().unwrap_or_else(|e| e.to_compile_error()));
+    }
+}
+
+struct OverloadableGlobal {
+    attrs: Vec<Attribute>,
+    callable: Option<kw::callable>,
+    dynamic: Option<Token![dyn]>,
+    documented: Option<kw::documented>,
+    vis: Visibility,
+    name: Ident,
+    /// Fields of the struct, which the overloads can use through `self`.
+    fields: Option<(Brace, Punctuated<Field, Token![,]>)>,
+    _as_keyword: Token![as],
+    fns: Punctuated<ParsedFnDef, Token![,]>,
+}
+
+impl Parse for OverloadableGlobal {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let mut callable = None;
+        let mut dynamic = None;
+        let mut documented = None;
+        loop {
+            // `callable` could just as well be the name of the overload set.
+            if input.peek(kw::callable) && !input.peek2(Token![as]) {
+                callable = Some(input.parse()?);
+            } else if input.peek(kw::documented) && !input.peek2(Token![as]) {
+                documented = Some(input.parse()?);
+            } else if input.peek(Token![dyn]) {
+                dynamic = Some(input.parse()?);
+            } else {
+                break;
+            }
+        }
+        Ok(Self {
+            attrs,
+            callable,
+            dynamic,
+            documented,
+            vis: input.parse()?,
+            name: input.parse()?,
+            fields: if input.peek(Brace) {
+                let content;
+                Some((braced!(content in input), content.parse_terminated(Field::parse_named)?))
+            } else {
+                None
+            },
+            _as_keyword: input.parse()?,
+            fns: input.parse_terminated(ParsedFnDef::parse)?,
+        })
+    }
+}
+
+mod kw {
+    syn::custom_keyword!(callable);
+    syn::custom_keyword!(coercible_to);
+    syn::custom_keyword!(documented);
+    syn::custom_keyword!(example);
+    syn::custom_keyword!(grouped);
+    syn::custom_keyword!(inherent);
+    syn::custom_keyword!(repeat);
+}
+
+struct OverloadableAssociated {
+    grouped: Option<kw::grouped>,
+    /// Requests an inherent function forwarding to the overload without a receiver.
+    inherent: Option<kw::inherent>,
+    vis: Visibility,
+    /// The structs which each get the overloads, written as `(Foo, Bar)` for more than one.
+    struct_names: Vec<Ident>,
+    struct_gen: Generics,
+    _colons: Token![::],
+    name: Ident,
+    _as: Token![as],
+    items: Punctuated<MemberItem, Token![,]>,
+}
+
+/// An associated `const` declared among member overloads.
+struct MemberConst {
+    attrs: Vec<Attribute>,
+    _const: Token![const],
+    ident: Ident,
+    _colon: Token![:],
+    ty: Type,
+    _eq: Token![=],
+    value: Expr,
+}
+
+impl Parse for MemberConst {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            attrs: input.call(Attribute::parse_outer)?,
+            _const: input.parse()?,
+            ident: input.parse()?,
+            _colon: input.parse()?,
+            ty: input.parse()?,
+            _eq: input.parse()?,
+            value: input.parse()?,
+        })
+    }
+}
+
+// Only ever parsed into and taken apart, so the size of the variants doesn't matter.
+#[allow(clippy::large_enum_variant)]
+enum MemberItem {
+    Fn(ParsedFnDef),
+    Const(MemberConst),
+}
+
+impl Parse for MemberItem {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let fork = input.fork();
+        fork.call(Attribute::parse_outer)?;
+        // `const NAME: Type`, as opposed to `const fn`.
+        if fork.peek(Token![const]) && fork.peek3(Token![:]) {
+            input.parse().map(MemberItem::Const)
+        } else {
+            input.parse().map(MemberItem::Fn)
+        }
+    }
+}
+
+impl Parse for OverloadableAssociated {
+    fn parse(input: ParseStream) -> Result<Self> {
+        // `grouped` and `inherent` could just as well be the name of the struct.
+        let prefix = |input: ParseStream| !input.peek2(Token![::]) && !input.peek2(Token![<]);
+        let mut grouped = None;
+        let mut inherent = None;
+        loop {
+            if input.peek(kw::grouped) && prefix(input) {
+                grouped = Some(input.parse()?);
+            } else if input.peek(kw::inherent) && prefix(input) {
+                inherent = Some(input.parse()?);
+            } else {
+                break;
+            }
+        }
+        let vis = input.parse()?;
+        let (struct_names, mut struct_gen) = if input.peek(Paren) {
+            let content;
+            let paren = parenthesized!(content in input);
+            let struct_names: Punctuated<Ident, Token![,]> = content.parse_terminated(Ident::parse)?;
+            if struct_names.is_empty() {
+                return Err(Error::new(paren.span, "Expected at least one struct name."));
+            }
+            if input.peek(Token![<]) {
+                return Err(input.error("Generics are only supported for a single struct."));
+            }
+            (struct_names.into_iter().collect(), Generics::default())
+        } else {
+            (vec![input.parse()?], input.parse()?)
+        };
+        let _colons = input.parse()?;
+        let name = input.parse()?;
+        // Bounds on the struct's generics, which apply to every overload.
+        if input.peek(Token![where]) {
+            struct_gen.where_clause = Some(input.parse()?);
+        }
+        Ok(Self {
+            grouped,
+            inherent,
+            vis,
+            struct_names,
+            struct_gen,
+            _colons,
+            name,
+            _as: input.parse()?,
+            items: input.parse_terminated(MemberItem::parse)?,
+        })
+    }
+}
+
+#[derive(Clone)]
+enum ThisDef {
+    Explicit(
+        Option<Token![mut]>,
+        Token![self],
+        Token![:],
+        Type,
+        Option<Token![,]>,
+    ),
+    Implicit(
+        Option<Token![&]>,
+        Option<Lifetime>,
+        Option<Token![mut]>,
+        Token![self],
+        Option<Token![,]>,
+    ),
+}
+
+impl Parse for ThisDef {
+    fn parse(input: ParseStream) -> Result<Self> {
+        // Try each form on a fork first, so that a failed attempt doesn't
+        // consume any of the parameter list.
+        if ThisDef::parse_explicit(&input.fork()).is_ok() {
+            ThisDef::parse_explicit(input)
+        } else if ThisDef::parse_implicit(&input.fork()).is_ok() {
+            ThisDef::parse_implicit(input)
+        } else {
+            Err(input.error(ThisDef::NOT_FOUND))
+        }
+    }
+}
+
+impl ToTokens for ThisDef {
+    fn to_tokens(&self, tokens: &mut Tok2) {
+        match self {
+            ThisDef::Explicit(mut_def, self_def, colon, ty, comma) => {
+                mut_def.to_tokens(tokens);
+                self_def.to_tokens(tokens);
+                colon.to_tokens(tokens);
+                ty.to_tokens(tokens);
+                comma.to_tokens(tokens);
+            }
+            ThisDef::Implicit(and, lifetime, mut_def, self_def, comma) => {
+                and.to_tokens(tokens);
+                lifetime.to_tokens(tokens);
+                mut_def.to_tokens(tokens);
+                self_def.to_tokens(tokens);
+                comma.to_tokens(tokens);
+            }
+        }
+    }
+}
+
+impl ThisDef {
+    const NOT_FOUND: &'static str =
+        "Could not find self type! Expected `self`, `&self`, `&mut self`, or `self: Type`.";
+
+    /// Whether the first parameter in `input` mentions `self`, meaning that it was
+    /// presumably meant as a receiver.
+    fn is_attempted(input: ParseStream) -> bool {
+        let mut cursor = input.cursor();
+        while let Some((tree, next)) = cursor.token_tree() {
+            match tree {
+                TokenTree::Punct(ref punct) if punct.as_char() == ',' => return false,
+                TokenTree::Ident(ref ident) if ident == "self" => return true,
+                _ => cursor = next,
+            }
+        }
+        false
+    }
+
+    /// `[mut] self: Type`
+    fn parse_explicit(input: ParseStream) -> Result<Self> {
+        let this = ThisDef::Explicit(
+            input.parse()?,
+            input.parse()?,
+            input.parse()?,
+            input.parse()?,
+            input.parse()?,
+        );
+        Self::expect_end(input, this)
+    }
+
+    /// `[&['a] [mut]] self`, or `mut self`
+    fn parse_implicit(input: ParseStream) -> Result<Self> {
+        let and: Option<Token![&]> = input.parse()?;
+        let lifetime = if and.is_some() && input.peek(Lifetime) {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        let this = ThisDef::Implicit(and, lifetime, input.parse()?, input.parse()?, input.parse()?);
+        Self::expect_end(input, this)
+    }
+
+    /// A receiver without a trailing comma must be the only parameter.
+    fn expect_end(input: ParseStream, this: Self) -> Result<Self> {
+        match this {
+            ThisDef::Explicit(.., None) | ThisDef::Implicit(.., None) if !input.is_empty() => {
+                Err(input.error("expected `,`"))
+            }
+            this => Ok(this),
+        }
+    }
+
+    pub fn is_sized_dependent(this: &Option<Self>) -> bool {
+        matches!(this, Some(ThisDef::Implicit(None, ..)))
+    }
+
+    /// The receiver as written in a trait declaration, where a `mut` binding
+    /// is not allowed since there is no body.
+    pub fn decl_tokens(&self) -> Tok2 {
+        match self {
+            ThisDef::Explicit(_, self_def, colon, ty, comma) => quote!(#self_def #colon #ty #comma),
+            ThisDef::Implicit(Some(and), lifetime, mut_def, self_def, comma) => {
+                quote!(#and #lifetime #mut_def #self_def #comma)
+            }
+            ThisDef::Implicit(None, _, _, self_def, comma) => quote!(#self_def #comma),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct ParsedFnDef {
+    meta: Vec<(Meta, Bracket)>,
+    vis: Visibility,
+    constness: Option<Token![const]>,
+    asyncness: Option<Token![async]>,
+    unsafety: Option<Token![unsafe]>,
+    abi: Option<Abi>,
+    _func: Token![fn],
+    trait_name: Option<(Token![as], Ident)>,
+    /// The `repeat N in ...` of the generics, if any.
+    repeat: Option<Repeat>,
+    gen: Option<Generics>,
+    paren: Paren,
+    this: Option<ThisDef>,
+    params: Punctuated<(Pat, Token![:], Type), Token![,]>,
+    /// The `#[default(...)]` value of each parameter, if any.
+    defaults: Vec<Option<Expr>>,
+    /// The lint attributes of each parameter, such as `#[allow(unused_variables)]`.
+    param_attrs: Vec<Vec<Attribute>>,
+    /// The function pointer types of any `#[coercible_to(...)]` attributes.
+    coercions: Vec<Type>,
+    /// The expressions of any `#[example(...)]` attributes, which should hold.
+    examples: Vec<Expr>,
+    ret: ReturnType,
+    w_clause: Option<WhereClause>,
+    code: Block,
+}
+
+type ParsedParam = (Vec<Attribute>, Option<Expr>, (Pat, Token![:], Type));
+
+/// The attributes which can be applied to a parameter's binding, besides `#[default(...)]`.
+const PARAM_LINT_ATTRS: &[&str] = &["allow", "warn", "deny", "forbid", "expect"];
+
+fn parse_pattern_type_pair(input: ParseStream) -> Result<ParsedParam> {
+    let mut default = None;
+    let mut attrs = Vec::new();
+    for attr in input.call(Attribute::parse_outer)? {
+        if PARAM_LINT_ATTRS.iter().any(|name| attr.path.is_ident(name)) {
+            attrs.push(attr);
+            continue;
+        }
+        if !attr.path.is_ident("default") || default.is_some() {
+            return Err(Error::new_spanned(
+                attr,
+                "Parameters can only have lint attributes, such as `#[allow(unused_variables)]`, \
+                 and a single `#[default(...)]` attribute.",
+            ));
+        }
+        let parse_default = |input: ParseStream| {
+            let content;
+            parenthesized!(content in input);
+            content.parse::<Expr>()
+        };
+        default = Some(parse_default.parse2(attr.tts)?);
+    }
+    Ok((attrs, default, (input.parse()?, input.parse()?, input.parse()?)))
+}
+
+/// Rejects patterns which are obviously refutable, since parameters are destructured
+/// directly from their argument tuple, unless the overload is `#[dispatch]`ed.
+fn check_irrefutable(pat: &Pat) -> Result<()> {
+    let refutable = |pat: &Pat| {
+        Err(Error::new_spanned(
+            pat,
+            "Parameters must be irrefutable, so patterns such as literals, ranges and \
+             enum variants cannot be used here, unless the overload is `#[dispatch]`ed.",
+        ))
+    };
+    match pat {
+        Pat::Lit(_) | Pat::Range(_) | Pat::Path(_) => refutable(pat),
+        Pat::Ident(ident) => match &ident.subpat {
+            Some((_, subpat)) => check_irrefutable(subpat),
+            None => Ok(()),
+        },
+        Pat::Struct(pat) => pat.fields.iter().try_for_each(|field| check_irrefutable(&field.pat)),
+        Pat::TupleStruct(pat) => pat.pat.front.iter().chain(&pat.pat.back).try_for_each(check_irrefutable),
+        Pat::Tuple(pat) => pat.front.iter().chain(&pat.back).try_for_each(check_irrefutable),
+        Pat::Box(pat) => check_irrefutable(&pat.pat),
+        Pat::Ref(pat) => check_irrefutable(&pat.pat),
+        Pat::Slice(pat) => pat
+            .front
+            .iter()
+            .chain(pat.middle.as_deref())
+            .chain(&pat.back)
+            .try_for_each(check_irrefutable),
+        _ => Ok(()),
+    }
+}
+
+/// The attributes of an overload, split into the `Meta`s of ordinary ones and the
+/// contents of `#[coercible_to(...)]` and `#[example(...)]`.
+type FnAttrs = (Vec<(Meta, Bracket)>, Vec<Type>, Vec<Expr>);
+
+fn split_fn_attrs(attrs: Vec<Attribute>) -> Result<FnAttrs> {
+    let mut meta = Vec::new();
+    let mut coercions = Vec::new();
+    let mut examples = Vec::new();
+    let parenthesized = |attr: &Attribute| {
+        matches!(attr.tts.clone().into_iter().next(), Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis)
+    };
+    for attr in attrs {
+        // This holds a type, which isn't valid in a `Meta`.
+        if attr.path.is_ident("coercible_to") && parenthesized(&attr) {
+            let parse = |input: ParseStream| {
+                let content;
+                parenthesized!(content in input);
+                content.parse::<Type>()
+            };
+            coercions.push(parse.parse2(attr.tts)?);
+            continue;
+        }
+        // As does this, with an expression.
+        if attr.path.is_ident("example") && parenthesized(&attr) {
+            let parse = |input: ParseStream| {
+                let content;
+                parenthesized!(content in input);
+                content.parse::<Expr>()
+            };
+            examples.push(parse.parse2(attr.tts)?);
+            continue;
+        }
+        meta.push((attr.parse_meta()?, attr.bracket_token));
+    }
+    Ok((meta, coercions, examples))
+}
+
+impl Parse for ParsedFnDef {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let (meta, coercions, examples) = split_fn_attrs(input.call(Attribute::parse_outer)?)?;
+        let vis = input.parse()?;
+        let constness = input.parse()?;
+        let asyncness = input.parse()?;
+        let unsafety = input.parse()?;
+        let abi = if input.peek(Token![extern]) {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        let _func = input.parse::<Token![fn]>()?;
+        let trait_name = if input.peek(Token![as]) {
+            Some((input.parse()?, input.parse()?))
+        } else {
+            None
+        };
+        let (repeat, gen) = if input.peek(Token![<]) && input.peek2(kw::repeat) {
+            Repeat::parse_generics(input)?
+        } else if input.peek(Token![<]) {
+            (None, Some(input.parse::<Generics>()?))
+        } else {
+            (None, None)
+        };
+        let params_content;
+        let paren = parenthesized!(params_content in input);
+        let this = match params_content.parse::<ThisDef>() {
+            Ok(this) => Some(this),
+            // Point at the whole parameter list, rather than wherever the
+            // receiver stopped making sense.
+            Err(_) if ThisDef::is_attempted(&params_content) => {
+                return Err(Error::new(paren.span, ThisDef::NOT_FOUND));
+            }
+            Err(_) => None,
+        };
+        let mut params = Punctuated::new();
+        let mut defaults = Vec::new();
+        let mut param_attrs = Vec::new();
+        let dispatch = meta.iter().any(|(m, _)| matches!(m, Meta::Word(word) if word == "dispatch"));
+        for pair in params_content.parse_terminated::<_, Token![,]>(parse_pattern_type_pair)?.into_pairs() {
+            let ((attrs, default, param), comma) = pair.into_tuple();
+            if !dispatch {
+                check_irrefutable(&param.0)?;
+            }
+            if default.is_none() && defaults.iter().any(Option::is_some) {
+                return Err(Error::new_spanned(
+                    &param.0,
+                    "Parameters without a `#[default(...)]` cannot follow ones with a default.",
+                ));
+            }
+            params.push_value(param);
+            if let Some(comma) = comma {
+                params.push_punct(comma);
+            }
+            defaults.push(default);
+            param_attrs.push(attrs);
+        }
+        let ret = input.parse()?;
+        let w_clause = if input.peek(Token![where]) {
+            Some(input.parse::<WhereClause>()?)
+        } else {
+            None
+        };
+        // `=> expr` is shorthand for a block holding only `expr`.
+        let code = if input.peek(Token![=>]) {
+            input.parse::<Token![=>]>()?;
+            let expr: Expr = input.parse()?;
+            Block {
+                brace_token: Brace(expr.span()),
+                stmts: vec![Stmt::Expr(expr)],
+            }
+        } else {
+            input.parse()?
+        };
+        let gen = declare_where_lifetimes(gen, w_clause.as_ref());
+        Ok(Self {
+            meta,
+            vis,
+            constness,
+            asyncness,
+            unsafety,
+            abi,
+            _func,
+            trait_name,
+            repeat,
+            gen,
+            paren,
+            this,
+            params,
+            defaults,
+            param_attrs,
+            coercions,
+            examples,
+            ret,
+            w_clause,
+            code,
+        })
+    }
+}
+
+impl ParsedFnDef {
+    /// The overload given to an [`OverloadSet`], as it would have been parsed from
+    /// the macros' syntax.
+    fn from_overload(overload: Overload, member: bool) -> Result<Self> {
+        let Overload { attrs, generics, receiver, params, output, body } = overload;
+        let (meta, coercions, examples) = split_fn_attrs(attrs)?;
+        let comma = if params.is_empty() { None } else { Some(Default::default()) };
+        let this = match receiver {
+            None => None,
+            Some(receiver) if !member => {
+                return Err(Error::new_spanned(receiver, "This declaration cannot contain a `self`-style parameter."));
+            }
+            Some(FnArg::SelfRef(ArgSelfRef { and_token, lifetime, mutability, self_token })) => {
+                Some(ThisDef::Implicit(Some(and_token), lifetime, mutability, self_token, comma))
+            }
+            Some(FnArg::SelfValue(ArgSelf { mutability, self_token })) => Some(ThisDef::Implicit(None, None, mutability, self_token, comma)),
+            Some(FnArg::Captured(ArgCaptured { pat: Pat::Ident(PatIdent { by_ref: None, mutability, ident, subpat: None }), colon_token, ty }))
+                if ident == "self" =>
+            {
+                Some(ThisDef::Explicit(mutability, Token![self](ident.span()), colon_token, ty, comma))
+            }
+            Some(receiver) => return Err(Error::new_spanned(receiver, ThisDef::NOT_FOUND)),
+        };
+        let dispatch = meta.iter().any(|(m, _)| matches!(m, Meta::Word(word) if word == "dispatch"));
+        if !dispatch {
+            params.iter().try_for_each(|(pat, _)| check_irrefutable(pat))?;
+        }
+        let count = params.len();
+        let params = params.into_iter().map(|(pat, ty)| (pat, Default::default(), ty)).collect();
+        let Generics { lt_token, params: gen_params, gt_token, where_clause } = generics;
+        let gen = if gen_params.is_empty() {
+            None
+        } else {
+            Some(Generics { lt_token, params: gen_params, gt_token, where_clause: None })
+        };
+        Ok(Self {
+            meta,
+            vis: Visibility::Inherited,
+            constness: None,
+            asyncness: None,
+            unsafety: None,
+            abi: None,
+            _func: Default::default(),
+            trait_name: None,
+            repeat: None,
+            gen: declare_where_lifetimes(gen, where_clause.as_ref()),
+            paren: Default::default(),
+            this,
+            params,
+            defaults: vec![None; count],
+            param_attrs: vec![Vec::new(); count],
+            coercions,
+            examples,
+            ret: output,
+            w_clause: where_clause,
+            code: body,
+        })
+    }
+}
+
+/// Declares the lifetimes which are only named in `w_clause`, as in
+/// `where 'a: 'b`, so that they needn't be repeated in the generics.
+fn declare_where_lifetimes(gen: Option<Generics>, w_clause: Option<&WhereClause>) -> Option<Generics> {
+    let mut collect = LifetimeCollect::default();
+    match w_clause {
+        Some(w_clause) => collect.visit_where_clause_mut(&mut w_clause.clone()),
+        None => return gen,
+    }
+    let declared: HashSet<_> = gen
+        .iter()
+        .flat_map(Generics::lifetimes)
+        .map(|def| def.lifetime.ident.to_string())
+        .collect();
+    let LifetimeCollect { named, higher_ranked } = collect;
+    let undeclared: Vec<_> = named
+        .into_iter()
+        .filter(|lifetime| {
+            let ident = lifetime.ident.to_string();
+            ident != "static" && ident != "_" && !declared.contains(&ident) && !higher_ranked.contains(&ident)
+        })
+        .collect();
+    if undeclared.is_empty() {
+        return gen;
+    }
+    let mut gen = gen.unwrap_or_default();
+    // Lifetimes have to come before the other parameters.
+    let lifetimes = declared.len();
+    for (i, lifetime) in undeclared.into_iter().enumerate() {
+        gen.params.insert(lifetimes + i, GenericParam::Lifetime(LifetimeDef::new(lifetime)));
+    }
+    Some(gen)
+}
+
+/// A `repeat N in 1..=4` in the generics of an overload, which declares a copy of it
+/// for each value of `N` in the range.
+#[derive(Clone)]
+struct Repeat {
+    ident: Ident,
+    counts: Vec<usize>,
+}
+
+impl Repeat {
+    /// Parses generics starting with `<repeat N in range`, along with whichever
+    /// ordinary generic parameters follow it.
+    fn parse_generics(input: ParseStream) -> Result<(Option<Self>, Option<Generics>)> {
+        let lt_token: Token![<] = input.parse()?;
+        input.parse::<kw::repeat>()?;
+        let ident = input.parse()?;
+        input.parse::<Token![in]>()?;
+        let start: syn::LitInt = input.parse()?;
+        let inclusive = if input.peek(Token![..=]) {
+            input.parse::<Token![..=]>()?;
+            true
+        } else {
+            input.parse::<Token![..]>()?;
+            false
+        };
+        let end: syn::LitInt = input.parse()?;
+        let (from, to) = (start.value() as usize, end.value() as usize);
+        let counts: Vec<usize> = if inclusive { (from..=to).collect() } else { (from..to).collect() };
+        if counts.is_empty() {
+            return Err(Error::new(end.span(), "The range of a `repeat` must not be empty."));
+        }
+        let mut params = Punctuated::new();
+        while input.parse::<Option<Token![,]>>()?.is_some() && !input.peek(Token![>]) {
+            params.push(input.parse::<GenericParam>()?);
+        }
+        let gt_token: Token![>] = input.parse()?;
+        let gen = if params.is_empty() {
+            None
+        } else {
+            Some(Generics { lt_token: Some(lt_token), params, gt_token: Some(gt_token), where_clause: None })
+        };
+        Ok((Some(Repeat { ident, counts }), gen))
+    }
+}
+
+/// Replaces uses of the count of a `repeat` in a signature with its value.
+struct RepeatReplace<'a> {
+    ident: &'a Ident,
+    count: usize,
+}
+
+impl VisitMut for RepeatReplace<'_> {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Path(path) if path.qself.is_none() && path.path.is_ident(self.ident.clone()) => {
+                let count = proc_macro2::Literal::usize_unsuffixed(self.count);
+                *expr = parse_quote!(#count);
+            }
+            _ => visit_mut::visit_expr_mut(self, expr),
+        }
+    }
+}
+
+/// Replaces the `Self` type with the struct declared by a global macro, since
+/// signatures end up outside of the impls where `Self` would otherwise resolve.
+struct SelfReplace<'a> {
+    name: &'a Ident,
+}
+
+impl VisitMut for SelfReplace<'_> {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        match ty {
+            Type::Path(TypePath { qself: None, path }) if path.is_ident("Self") => {
+                let name = self.name;
+                *ty = parse_quote!(#name);
+            }
+            _ => visit_mut::visit_type_mut(self, ty),
+        }
+    }
+}
+
+/// Collects the lifetimes named in a where clause, besides those introduced by a
+/// `for<...>` within it.
+#[derive(Default)]
+struct LifetimeCollect {
+    named: Vec<Lifetime>,
+    higher_ranked: HashSet<String>,
+}
+
+impl VisitMut for LifetimeCollect {
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if !self.named.contains(lifetime) {
+            self.named.push(lifetime.clone());
+        }
+    }
+
+    fn visit_bound_lifetimes_mut(&mut self, bound: &mut BoundLifetimes) {
+        for def in bound.lifetimes.iter() {
+            self.higher_ranked.insert(def.lifetime.ident.to_string());
+        }
+    }
+}
+
+/// Collects the types given to associated types in bounds, as in `Iterator<Item = U>`,
+/// which determine the parameters they name.
+#[derive(Default)]
+struct BindingCollect {
+    types: Vec<Type>,
+}
+
+impl VisitMut for BindingCollect {
+    fn visit_binding_mut(&mut self, binding: &mut Binding) {
+        self.types.push(binding.ty.clone());
+        visit_mut::visit_binding_mut(self, binding);
+    }
+}
+
+/// Counts the lifetimes in a type, elided or not, or gives the elided ones `fill`,
+/// besides those in `fn` types and `Fn(...)` bounds, which elide on their own.
+#[derive(Default)]
+struct ElidedLifetimes {
+    fill: Option<Lifetime>,
+    named: Vec<Lifetime>,
+    elided: usize,
+}
+
+impl VisitMut for ElidedLifetimes {
+    fn visit_type_reference_mut(&mut self, reference: &mut TypeReference) {
+        if reference.lifetime.is_none() {
+            self.elided += 1;
+            reference.lifetime = self.fill.clone();
+        }
+        visit_mut::visit_type_reference_mut(self, reference);
+    }
+
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if lifetime.ident != "_" {
+            self.named.push(lifetime.clone());
+            return;
+        }
+        self.elided += 1;
+        if let Some(fill) = &self.fill {
+            *lifetime = fill.clone();
+        }
+    }
+
+    fn visit_type_bare_fn_mut(&mut self, _: &mut TypeBareFn) {}
+
+    fn visit_parenthesized_generic_arguments_mut(&mut self, _: &mut ParenthesizedGenericArguments) {}
+}
+
+/// Replaces the lifetimes of a type named in `names`, or all of them, elided or not,
+/// if it is `None`, with `with`, besides those in `fn` types and `Fn(...)` bounds.
+struct ReplaceLifetimes<'a> {
+    names: Option<&'a [&'a Lifetime]>,
+    with: &'static str,
+}
+
+impl VisitMut for ReplaceLifetimes<'_> {
+    fn visit_type_reference_mut(&mut self, reference: &mut TypeReference) {
+        if reference.lifetime.is_none() && self.names.is_none() {
+            reference.lifetime = Some(Lifetime::new(self.with, reference.and_token.spans[0]));
+        }
+        visit_mut::visit_type_reference_mut(self, reference);
+    }
+
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if self.names.is_none_or(|names| names.contains(&&*lifetime)) {
+            *lifetime = Lifetime::new(self.with, lifetime.span());
+        }
+    }
+
+    fn visit_type_bare_fn_mut(&mut self, _: &mut TypeBareFn) {}
+
+    fn visit_parenthesized_generic_arguments_mut(&mut self, _: &mut ParenthesizedGenericArguments) {}
+}
+
+/// Replaces each `impl Trait` in argument position with a fresh type parameter.
+struct ImplTraitDesugar {
+    params: Vec<TypeParam>,
+}
+
+impl VisitMut for ImplTraitDesugar {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        visit_mut::visit_type_mut(self, ty);
+        if let Type::ImplTrait(TypeImplTrait { bounds, .. }) = ty {
+            let ident = Ident::new(
+                &format!("__OverloadableImpl{}", self.params.len()),
+                bounds.span(),
+            );
+            self.params.push(TypeParam {
+                attrs: Vec::new(),
+                ident: ident.clone(),
+                colon_token: Some(Default::default()),
+                bounds: bounds.clone(),
+                eq_token: None,
+                default: None,
+            });
+            *ty = Type::Path(TypePath {
+                qself: None,
+                path: ident.into(),
+            });
+        }
+    }
+}
+
+/// Removes a `#[name]` attribute from `meta`, returning whether it was present.
+fn take_meta_word(meta: &mut Vec<(Meta, Bracket)>, name: &str) -> bool {
+    let len = meta.len();
+    meta.retain(|(m, _)| match m {
+        Meta::Word(ident) => ident != name,
+        _ => true,
+    });
+    meta.len() != len
+}
+
+type BracketedMeta = (Meta, Bracket);
+
+/// Whether `meta` is `#[coerce_via(Into)]`.
+fn is_coerce_via_into(meta: &Meta) -> bool {
+    match meta {
+        Meta::List(list) if list.ident == "coerce_via" && list.nested.len() == 1 => {
+            matches!(&list.nested[0], NestedMeta::Meta(Meta::Word(word)) if word == "Into")
+        }
+        _ => false,
+    }
+}
+
+/// Merges the `#[dispatch]` overloads taking the same argument types into the first
+/// of them, whose body then matches the arguments against each of their patterns in
+/// turn, so that they may be refutable, as in `fn(Some(x): Option<u8>)`.
+fn merge_dispatch(fns: Vec<ParsedFnDef>) -> Result<Vec<ParsedFnDef>> {
+    let mut merged: Vec<(ParsedFnDef, Option<Vec<Tok2>>)> = Vec::new();
+    // The index in `merged` of the overload for each list of argument types.
+    let mut groups = HashMap::new();
+    // Everything that has to agree between the overloads which are merged.
+    let shape = |def: &ParsedFnDef| {
+        let ParsedFnDef { asyncness, unsafety, gen, ret, w_clause, .. } = def;
+        quote!(#asyncness #unsafety #gen #ret #w_clause).to_string()
+    };
+    for mut def in fns {
+        if !take_meta_word(&mut def.meta, "dispatch") {
+            merged.push((def, None));
+            continue;
+        }
+        if let Some(attr) = def.param_attrs.iter().flatten().next() {
+            return Err(Error::new_spanned(attr, "The parameters of `#[dispatch]` overloads cannot have attributes, since they are matched against."));
+        }
+        let types = def.params.iter().map(|(_, _, ty)| ty);
+        let key = quote!(#(#types,)*).to_string();
+        let pats = def.params.iter().map(|(pat, _, _)| pat);
+        let code = &def.code;
+        let arm = quote!((#(#pats,)*) => #code);
+        match groups.get(&key) {
+            Some(&index) => {
+                let (first, arms): &mut (ParsedFnDef, Option<Vec<Tok2>>) = &mut merged[index];
+                if shape(first) != shape(&def) || !def.meta.is_empty() {
+                    return Err(Error::new(
+                        def.paren.span,
+                        "`#[dispatch]` overloads taking the same argument types must have the same generics, \
+                         qualifiers and return type, and only the first of them can have other attributes.",
+                    ));
+                }
+                if let Some(arms) = arms {
+                    arms.push(arm);
+                }
+            }
+            None => {
+                groups.insert(key, merged.len());
+                merged.push((def, Some(vec![arm])));
+            }
+        }
+    }
+    Ok(merged
+        .into_iter()
+        .map(|(mut def, arms)| {
+            let arms = match arms {
+                Some(arms) => arms,
+                None => return def,
+            };
+            let span = def.paren.span;
+            let args: Vec<Ident> = (0..def.params.len()).map(|i| Ident::new(&format!("__overloadable_arg{}", i), span)).collect();
+            for ((pat, _, _), arg) in def.params.iter_mut().zip(&args) {
+                *pat = parse_quote!(#arg);
+            }
+            // Any patterns left unmatched are reported at the first overload.
+            let matched = quote_spanned!(span => match (#(#args,)*) { #(#arms),* });
+            def.code = parse_quote!({ #matched });
+            def
+        })
+        .collect())
+}
+
+/// Errors on `#[coerce_via(Into)]` overloads taking as many arguments as another
+/// overload, since their impls would overlap.
+fn check_coerce_via_arity(fns: &[ParsedFnDef]) -> Result<()> {
+    for (index, def) in fns.iter().enumerate() {
+        let attr = match def.meta.iter().find(|(m, _)| is_coerce_via_into(m)) {
+            Some((attr, _)) => attr,
+            None => continue,
+        };
+        let arity = def.params.len();
+        if fns.iter().enumerate().any(|(other, def)| other != index && def.params.len() == arity) {
+            return Err(Error::new_spanned(
+                attr,
+                format!(
+                    "A `#[coerce_via(Into)]` overload can't share its number of arguments, {}, with another overload, \
+                     since any argument types might convert into its own.",
+                    arity
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Whether `meta` is an optimization hint, which is applied to every `Fn*` method.
+fn is_propagated_meta(meta: &Meta) -> bool {
+    meta.name() == "inline" || meta.name() == "cold"
+}
+
+/// Separates the optimization hints in `meta` from the other attributes, splitting a
+/// `#[cfg_attr(...)]` holding both into one for each, under the same predicate.
+fn partition_propagated_meta(meta: Vec<BracketedMeta>) -> (Vec<BracketedMeta>, Vec<BracketedMeta>) {
+    let mut propagated = Vec::new();
+    let mut rest = Vec::new();
+    for (m, bracket) in meta {
+        let list = match m {
+            Meta::List(list) if list.ident == "cfg_attr" && list.nested.len() > 1 => list,
+            m if is_propagated_meta(&m) => {
+                propagated.push((m, bracket));
+                continue;
+            }
+            m => {
+                rest.push((m, bracket));
+                continue;
+            }
+        };
+        let mut nested = list.nested.clone().into_iter();
+        let predicate = nested.next();
+        let (hints, others): (Vec<_>, Vec<_>) =
+            nested.partition(|attr| matches!(attr, NestedMeta::Meta(m) if is_propagated_meta(m)));
+        let rebuild = |attrs: Vec<NestedMeta>| {
+            let mut list = list.clone();
+            list.nested = predicate.iter().cloned().chain(attrs).collect();
+            (Meta::List(list), bracket)
+        };
+        if !hints.is_empty() {
+            propagated.push(rebuild(hints));
+        }
+        if !others.is_empty() {
+            rest.push(rebuild(others));
+        }
+    }
+    (propagated, rest)
+}
+
+/// The attributes applied by `meta` if it's a `#[cfg_attr(...)]`, or `meta` itself.
+fn cfg_attr_contents(meta: &Meta) -> Vec<&Meta> {
+    match meta {
+        Meta::List(list) if list.ident == "cfg_attr" => list
+            .nested
+            .iter()
+            .skip(1)
+            .filter_map(|attr| match attr {
+                NestedMeta::Meta(m) => Some(m),
+                NestedMeta::Literal(_) => None,
+            })
+            .collect(),
+        meta => vec![meta],
+    }
+}
+
+/// The predicate of `meta` if it's a `#[cfg_attr(...)]`.
+fn cfg_attr_predicate(meta: &Meta) -> Option<&NestedMeta> {
+    match meta {
+        Meta::List(list) if list.ident == "cfg_attr" => list.nested.first().map(|pair| pair.into_value()),
+        _ => None,
+    }
+}
+
+/// The predicate under which an overload is declared, combining all of its
+/// `#[cfg(...)]`s, if it has any.
+fn cfg_predicate(meta: &[(Meta, Bracket)]) -> Option<Tok2> {
+    let predicates: Vec<_> = meta
+        .iter()
+        .filter_map(|(m, _)| match m {
+            Meta::List(list) if list.ident == "cfg" => Some(&list.nested),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+    if predicates.is_empty() {
+        None
+    } else {
+        Some(quote!(all(#(#predicates),*)))
+    }
+}
+
+/// Checks the form of an attribute which is applied to every `Fn*` method.
+fn check_propagated_meta(meta: &Meta) -> Result<()> {
+    let well_formed = match meta {
+        Meta::Word(_) => true,
+        Meta::List(list) if meta.name() == "inline" && list.nested.len() == 1 => match &list.nested[0] {
+            NestedMeta::Meta(Meta::Word(word)) => word == "always" || word == "never",
+            _ => false,
+        },
+        _ => false,
+    };
+    if well_formed {
+        return Ok(());
+    }
+    let expected = if meta.name() == "inline" {
+        "`#[inline]`, `#[inline(always)]` or `#[inline(never)]`"
+    } else {
+        "`#[cold]`"
+    };
+    Err(Error::new_spanned(
+        meta,
+        format!(
+            "Expected {}. Unlike other attributes, this is applied to each of the `call`, \
+             `call_mut` and `call_once` methods, rather than to `call` only.",
+            expected
+        ),
+    ))
+}
+
+/// Removes any `#[clippy_allow(...)]` attributes from `meta`, returning an `#[allow]`
+/// for the generated wrappers which covers both the given lints and those which
+/// machine-generated signatures commonly trip.
+fn take_clippy_allows(meta: &mut Vec<(Meta, Bracket)>) -> Result<Tok2> {
+    let mut lints = vec![quote!(clippy::too_many_arguments), quote!(clippy::type_complexity)];
+    let mut error = None;
+    meta.retain(|(m, _)| {
+        if m.name() != "clippy_allow" {
+            return true;
+        }
+        let malformed = |tokens: &dyn ToTokens| {
+            Error::new_spanned(tokens, "Expected a list of clippy lint names, as in `#[clippy_allow(type_complexity)]`.")
+        };
+        match m {
+            Meta::List(list) => {
+                for nested in &list.nested {
+                    match nested {
+                        NestedMeta::Meta(Meta::Word(lint)) => lints.push(quote!(clippy::#lint)),
+                        nested => error = Some(malformed(nested)),
+                    }
+                }
+            }
+            m => error = Some(malformed(m)),
+        }
+        false
+    });
+    match error {
+        Some(error) => Err(error),
+        None => Ok(quote!(#[allow(#(#lints),*)])),
+    }
+}
+
+/// The highest priority an overload can be given, which bounds the references
+/// a prioritized call has to peel off.
+const MAX_OVERLOAD_PRIORITY: u64 = 15;
+
+/// The priority given by an `#[overload_priority(n)]` in `meta`, if it is well formed.
+fn overload_priority(meta: &[(Meta, Bracket)]) -> Option<u64> {
+    meta.iter().find_map(|(m, _)| match m {
+        Meta::List(list) if m.name() == "overload_priority" && list.nested.len() == 1 => match &list.nested[0] {
+            NestedMeta::Literal(Lit::Int(n)) if n.value() <= MAX_OVERLOAD_PRIORITY => Some(n.value()),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// Removes any `#[overload_priority(n)]` from `meta`, returning `n`.
+fn take_overload_priority(meta: &mut Vec<(Meta, Bracket)>) -> Result<Option<u64>> {
+    let priority = overload_priority(meta);
+    let mut found = meta.iter().filter(|(m, _)| m.name() == "overload_priority");
+    if let Some((m, _)) = found.next() {
+        if let Some((duplicate, _)) = found.next() {
+            return Err(Error::new_spanned(duplicate, "An overload can only be given one `#[overload_priority(...)]`."));
+        }
+        if priority.is_none() {
+            return Err(Error::new_spanned(
+                m,
+                format!("Expected `#[overload_priority(n)]`, where `n` is an integer from 0 to {}.", MAX_OVERLOAD_PRIORITY),
+            ));
+        }
+    }
+    meta.retain(|(m, _)| m.name() != "overload_priority");
+    Ok(priority)
+}
+
+/// Names the struct a prioritized call wraps its arguments in.
+fn prioritized_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}Prioritized", camel_case(&name.to_string())), name.span())
+}
+
+/// Names the trait implemented by the overloads of the given priority.
+fn priority_trait_name(name: &Ident, priority: u64) -> Ident {
+    Ident::new(&format!("{}Priority{}", camel_case(&name.to_string()), priority), name.span())
+}
+
+/// Declares a macro named after `callable` which calls the overload set `name` with
+/// the overload of the highest priority that its arguments could be given to.
+///
+/// Method resolution tries the receiver as is before peeling off each reference, so
+/// the arguments are wrapped in one more reference than the highest priority, and an
+/// overload of priority `n` is implemented for the wrapper behind `n` references.
+fn gen_priority_macro(callable: &Ident, name: &Ident, fns: &[ParsedFnDef]) -> Tok2 {
+    let highest = match fns.iter().filter_map(|def| overload_priority(&def.meta)).max() {
+        Some(highest) => highest,
+        None => return quote!(),
+    };
+    let prioritized_name = prioritized_name(name);
+    let refs = (0..=highest).map(|_| quote!(&));
+    quote!(
+        #[allow(unused_macros)]
+        macro_rules! #callable {
+            ($($arg:expr),* $(,)?) => {
+                (#(#refs)* #prioritized_name(::core::cell::Cell::new(::core::option::Option::Some(($($arg,)*)))))
+                    .__overloadable_call(&#name)
+            };
+        }
+    )
+}
+
+/// An overload's attributes, sorted by where they belong in the generated code.
+struct SplitMeta {
+    /// Attributes for the generated items as a whole, such as `#[cfg]`.
+    item: Vec<Tok2>,
+    /// Attributes which must also be present on a trait method's declaration,
+    /// such as `#[track_caller]`.
+    decl: Vec<Tok2>,
+    /// Attributes which only belong on a trait method's declaration, such as
+    /// `#[must_use]` or `#[deprecated]`.
+    decl_only: Vec<Tok2>,
+    /// Attributes for the method implementing the overload.
+    method: Vec<Tok2>,
+}
+
+fn split_meta(meta: &[(Meta, Bracket)]) -> SplitMeta {
+    let mut split = SplitMeta {
+        item: Vec::new(),
+        decl: Vec::new(),
+        decl_only: Vec::new(),
+        method: Vec::new(),
+    };
+    for (m, b) in meta {
+        let tokens = quote_spanned!(b.span => #[#m]);
+        if m.name() == "cfg" {
+            split.item.push(tokens);
+        } else if m.name() == "must_use" || m.name() == "deprecated" {
+            split.decl_only.push(tokens);
+        } else {
+            if m.name() == "track_caller" {
+                split.decl.push(tokens.clone());
+            }
+            split.method.push(tokens);
+        }
+    }
+    split
+}
+
+impl ParsedFnDef {
+    /// Handles `#[phantom]` by appending a trailing `PhantomData` argument which
+    /// mentions every generic parameter, so that none of them are unused.
+    fn inject_phantom(mut self) -> Self {
+        if !take_meta_word(&mut self.meta, "phantom") {
+            return self;
+        }
+        let markers: Vec<Tok2> = self
+            .gen
+            .iter()
+            .flat_map(|gen| gen.params.iter())
+            .map(|param| match param {
+                GenericParam::Type(TypeParam { ident, .. }) => quote!(#ident),
+                GenericParam::Lifetime(LifetimeDef { lifetime, .. }) => quote!(&#lifetime ()),
+                GenericParam::Const(ConstParam { ident, .. }) => quote!([(); #ident]),
+            })
+            .collect();
+        let ident = Ident::new("_overloadable_phantom", self.paren.span);
+        self.params.push((
+            parse_quote!(#ident),
+            Default::default(),
+            parse_quote!(::core::marker::PhantomData<(#(#markers,)*)>),
+        ));
+        self
+    }
+
+    /// Expands a `repeat N in ...` into one overload for each value of `N`, in which
+    /// each parameter of type `[T; N]` is taken as `N` separate arguments instead.
+    fn expand_repeat(self) -> Vec<Self> {
+        let repeat = match &self.repeat {
+            Some(repeat) => repeat.clone(),
+            None => return vec![self],
+        };
+        let mut expanded = Vec::new();
+        for &count in &repeat.counts {
+            let mut def = self.clone();
+            def.repeat = None;
+            let mut replace = RepeatReplace { ident: &repeat.ident, count };
+            let mut params = Punctuated::new();
+            let mut defaults = Vec::new();
+            let mut param_attrs = Vec::new();
+            let mut bindings = Vec::new();
+            let originals = self.params.iter().cloned().zip(self.defaults.iter().cloned()).zip(self.param_attrs.iter().cloned());
+            for (((pat, colon, mut ty), default), attrs) in originals {
+                let spread = match &ty {
+                    Type::Array(array) => match &array.len {
+                        Expr::Path(len) => len.qself.is_none() && len.path.is_ident(repeat.ident.clone()),
+                        _ => false,
+                    },
+                    _ => false,
+                };
+                replace.visit_type_mut(&mut ty);
+                if !spread {
+                    params.push((pat, colon, ty));
+                    defaults.push(default);
+                    param_attrs.push(attrs);
+                    continue;
+                }
+                let elem = match &ty {
+                    Type::Array(array) => &*array.elem,
+                    _ => unreachable!(),
+                };
+                let args: Vec<Ident> = (0..count)
+                    .map(|i| Ident::new(&format!("__repeat_{}_{}", bindings.len(), i), proc_macro2::Span::call_site()))
+                    .collect();
+                for arg in &args {
+                    params.push((parse_quote!(#arg), colon, elem.clone()));
+                    defaults.push(None);
+                    param_attrs.push(Vec::new());
+                }
+                bindings.push(quote!(#(#attrs)* let #pat: #ty = [#(#args),*];));
+            }
+            def.params = params;
+            def.defaults = defaults;
+            def.param_attrs = param_attrs;
+            if let ReturnType::Type(_, ty) = &mut def.ret {
+                replace.visit_type_mut(ty);
+            }
+            if let Some(gen) = &mut def.gen {
+                replace.visit_generics_mut(gen);
+            }
+            if let Some(w_clause) = &mut def.w_clause {
+                replace.visit_where_clause_mut(w_clause);
+            }
+            let ident = &repeat.ident;
+            let count: Stmt = parse_quote!(#[allow(dead_code)] const #ident: usize = #count;);
+            let bindings = bindings.into_iter().map(|binding| parse_quote!(#binding));
+            def.code.stmts.splice(0..0, std::iter::once(count).chain(bindings));
+            expanded.push(def);
+        }
+        expanded
+    }
+
+    /// Expands trailing `#[default(...)]` parameters into one overload for each
+    /// number of them which may be omitted, binding the omitted ones in the body.
+    fn expand_defaults(self) -> Vec<Self> {
+        let required = self.defaults.iter().take_while(|default| default.is_none()).count();
+        let mut expanded = Vec::new();
+        for len in (required..=self.params.len()).rev() {
+            let mut def = self.clone();
+            let omitted: Vec<_> = def.params.iter().skip(len).cloned().collect();
+            def.params = def.params.into_iter().take(len).collect();
+            let bindings = omitted
+                .iter()
+                .zip(&self.defaults[len..])
+                .zip(&self.param_attrs[len..])
+                .map(|(((pat, _, ty), default), attrs)| quote!(#(#attrs)* let #pat: #ty = #default;));
+            if !omitted.is_empty() {
+                def.code.stmts.splice(0..0, bindings.map(|binding| parse_quote!(#binding)));
+                // The checked function pointer types are those of the full signature.
+                def.coercions = Vec::new();
+            }
+            def.defaults = vec![None; len];
+            def.param_attrs.truncate(len);
+            expanded.push(def);
+        }
+        expanded
+    }
+
+    /// The signature of the overload as it would be written by hand, such as
+    /// `my_func(x: usize, y: &str) -> f32`.
+    fn signature(&self, name: &Ident) -> String {
+        let ParsedFnDef { gen, params, ret, w_clause, .. } = self;
+        let params = params.iter().map(|(pat, colon, ty)| quote!(#pat #colon #ty));
+        tidy_tokens(quote!(#name #gen (#(#params),*) #ret #w_clause))
+    }
+
+    /// Moves the attributes of each parameter onto a `let` binding its pattern at the
+    /// start of the body, since they can't be applied within the argument tuple.
+    fn bind_param_attrs(mut self) -> Self {
+        let mut bindings = Vec::new();
+        for (index, ((pat, _, ty), attrs)) in self.params.iter_mut().zip(&mut self.param_attrs).enumerate() {
+            if attrs.is_empty() {
+                continue;
+            }
+            let arg = Ident::new(&format!("__param_{}", index), proc_macro2::Span::call_site());
+            let attrs = std::mem::take(attrs);
+            bindings.push(quote!(#(#attrs)* let #pat: #ty = #arg;));
+            *pat = parse_quote!(#arg);
+        }
+        self.code.stmts.splice(0..0, bindings.into_iter().map(|binding| parse_quote!(#binding)));
+        self
+    }
+
+    /// Handles `#[coerce_via(Into)]` by taking each argument as any type which converts
+    /// into the declared one, converting it at the start of the body.
+    fn coerce_via_into(mut self) -> Self {
+        match self.meta.iter().position(|(m, _)| is_coerce_via_into(m)) {
+            Some(pos) => self.meta.remove(pos),
+            None => return self,
+        };
+        let mut gen = self.gen.take().unwrap_or_default();
+        let mut bindings = Vec::new();
+        for (index, (pat, _, ty)) in self.params.iter_mut().enumerate() {
+            let param = Ident::new(&format!("__OverloadableInto{}", index), ty.span());
+            let arg = Ident::new(&format!("__into_{}", index), proc_macro2::Span::call_site());
+            gen.params.push(parse_quote!(#param: ::core::convert::Into<#ty>));
+            bindings.push(quote!(let #pat: #ty = ::core::convert::Into::into(#arg);));
+            *pat = parse_quote!(#arg);
+            *ty = parse_quote!(#param);
+        }
+        // Spliced in, rather than nesting the block, which would have the braces of an
+        // `=> expr` body linted as unnecessary.
+        self.code.stmts.splice(0..0, bindings.into_iter().map(|binding| parse_quote!(#binding)));
+        self.gen = Some(gen);
+        self
+    }
+
+    /// Makes `Self` in the signature refer to the struct named `name`. Bodies are
+    /// left alone, since they are placed inside impls for it anyway.
+    fn replace_self_type(mut self, name: &Ident) -> Self {
+        let mut replace = SelfReplace { name };
+        for (_, _, ty) in self.params.iter_mut() {
+            replace.visit_type_mut(ty);
+        }
+        if let ReturnType::Type(_, ty) = &mut self.ret {
+            replace.visit_type_mut(ty);
+        }
+        if let Some(gen) = &mut self.gen {
+            replace.visit_generics_mut(gen);
+        }
+        if let Some(w_clause) = &mut self.w_clause {
+            replace.visit_where_clause_mut(w_clause);
+        }
+        for ty in &mut self.coercions {
+            replace.visit_type_mut(ty);
+        }
+        self
+    }
+
+    /// Gives the elided lifetimes of the return type that of the only argument with
+    /// one, as a function signature would, since the `Output` of an impl can't elide
+    /// them. An elided lifetime in the argument is declared as a parameter for this.
+    fn name_elided_output(mut self) -> Self {
+        let ret = match &mut self.ret {
+            ReturnType::Type(_, ty) => ty,
+            ReturnType::Default => return self,
+        };
+        let mut output = ElidedLifetimes::default();
+        output.visit_type_mut(&mut ret.clone());
+        if output.elided == 0 {
+            return self;
+        }
+        let mut inputs = ElidedLifetimes::default();
+        for (_, _, ty) in &mut self.params.clone() {
+            inputs.visit_type_mut(ty);
+        }
+        let lifetime = match (inputs.elided, &inputs.named[..]) {
+            (0, [lifetime]) => lifetime.clone(),
+            (1, []) => {
+                let lifetime = Lifetime::new("'__overloadable_elided", self.paren.span);
+                let mut fill = ElidedLifetimes { fill: Some(lifetime.clone()), ..Default::default() };
+                for (_, _, ty) in self.params.iter_mut() {
+                    fill.visit_type_mut(ty);
+                }
+                let gen = self.gen.get_or_insert_with(Generics::default);
+                gen.params.insert(0, GenericParam::Lifetime(LifetimeDef::new(lifetime.clone())));
+                lifetime
+            }
+            // Left for the compiler to point out, as it would for a function.
+            _ => return self,
+        };
+        ElidedLifetimes { fill: Some(lifetime), ..Default::default() }.visit_type_mut(ret);
+        self
+    }
+
+    fn desugar_impl_trait(mut self) -> Self {
+        let mut desugar = ImplTraitDesugar { params: Vec::new() };
+        for (_, _, ty) in self.params.iter_mut() {
+            desugar.visit_type_mut(ty);
+        }
+        if desugar.params.is_empty() {
+            return self;
+        }
+        // Lifetimes elided in a bound are each given one of their own, as they would be
+        // in argument position, whereas an impl's generics can't elide them.
+        let mut lifetimes = Vec::new();
+        for (index, param) in desugar.params.iter_mut().enumerate() {
+            let lifetime = Lifetime::new(&format!("'__overloadable_impl{}", index), param.ident.span());
+            let mut fill = ElidedLifetimes { fill: Some(lifetime.clone()), ..Default::default() };
+            for bound in param.bounds.iter_mut() {
+                fill.visit_type_param_bound_mut(bound);
+            }
+            if fill.elided > 0 {
+                lifetimes.push(lifetime);
+            }
+        }
+        // Those named in a bound are declared, as are those only named in the where clause.
+        let mut collect = LifetimeCollect::default();
+        for param in &mut desugar.params.clone() {
+            collect.visit_type_param_mut(param);
+        }
+        let gen = self.gen.get_or_insert_with(Generics::default);
+        let declared: HashSet<_> = gen.lifetimes().map(|def| def.lifetime.ident.to_string()).collect();
+        for lifetime in collect.named {
+            let ident = lifetime.ident.to_string();
+            let implicit = ident == "static" || ident == "_" || collect.higher_ranked.contains(&ident);
+            if !implicit && !declared.contains(&ident) && !lifetimes.contains(&lifetime) {
+                lifetimes.push(lifetime);
+            }
+        }
+        // Lifetimes have to come before the other parameters.
+        let position = declared.len();
+        for (i, lifetime) in lifetimes.into_iter().enumerate() {
+            gen.params.insert(position + i, GenericParam::Lifetime(LifetimeDef::new(lifetime)));
+        }
+        for param in desugar.params {
+            gen.params.push(GenericParam::Type(param));
+        }
+        self
+    }
+}
+
+/// Collects every identifier that appears in `tokens`, including inside groups.
+fn collect_idents(tokens: Tok2, idents: &mut HashSet<String>) {
+    for tree in tokens {
+        match tree {
+            TokenTree::Ident(ident) => {
+                idents.insert(ident.to_string());
+            }
+            TokenTree::Group(group) => collect_idents(group.stream(), idents),
+            _ => {}
+        }
+    }
+}
+
+/// Errors on type and const parameters which appear nowhere in `uses`, since
+/// they can't be inferred at the call site. Those given to an associated type in a
+/// bound, as `U` in `T: Iterator<Item = U>`, are determined by it, so count as used,
+/// unlike ones which only appear in other bounds, as `N` in `T: HasLen<N>`. `place`
+/// names what `uses` are in the error, such as the overload's arguments.
+fn check_generics_used(gen: &Option<Generics>, w_clause: &Option<WhereClause>, uses: Tok2, place: &str) -> Result<()> {
+    let gen = match gen {
+        Some(gen) => gen,
+        None => return Ok(()),
+    };
+    let mut bindings = BindingCollect::default();
+    bindings.visit_generics_mut(&mut gen.clone());
+    if let Some(w_clause) = w_clause {
+        bindings.visit_where_clause_mut(&mut w_clause.clone());
+    }
+    let bound = bindings.types;
+    let mut idents = HashSet::new();
+    collect_idents(quote!(#uses #(#bound)*), &mut idents);
+    for param in gen.params.iter() {
+        let ident = match param {
+            GenericParam::Type(TypeParam { ident, .. }) => ident,
+            GenericParam::Const(ConstParam { ident, .. }) => ident,
+            GenericParam::Lifetime(_) => continue,
+        };
+        if !idents.contains(&ident.to_string()) {
+            return Err(Error::new(
+                ident.span(),
+                format!(
+                    "The generic parameter `{0}` is never used in this overload's {1}. \
+                     Consider removing it, or adding a `PhantomData<{0}>` argument.",
+                    ident, place
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The subset of `gen`'s parameters which appear in `uses`.
+fn used_generics(gen: &Generics, uses: Tok2) -> Generics {
+    let mut idents = HashSet::new();
+    collect_idents(uses, &mut idents);
+    let params = gen
+        .params
+        .iter()
+        .filter(|param| {
+            let ident = match param {
+                GenericParam::Type(TypeParam { ident, .. }) => ident,
+                GenericParam::Const(ConstParam { ident, .. }) => ident,
+                GenericParam::Lifetime(LifetimeDef { lifetime, .. }) => &lifetime.ident,
+            };
+            idents.contains(&ident.to_string())
+        })
+        .cloned()
+        .collect();
+    Generics {
+        params,
+        ..Generics::default()
+    }
+}
+
+/// The path to `Box` through the module declared by `gen_alloc_mod`, which only exists
+/// with the `alloc` or `std` feature.
+fn box_path(alloc_mod: &Ident) -> Option<Tok2> {
+    if cfg!(feature = "alloc") {
+        Some(quote!(#alloc_mod::Box))
+    } else {
+        None
+    }
+}
+
+/// Declares a module re-exporting `Box`, if the `alloc` or `std` feature is enabled.
+/// Which of them is enabled says nothing about whether the invoking crate is
+/// `#![no_std]`, since the features of every crate depending on `overloadable` are
+/// unified, but `extern crate alloc` works either way.
+fn gen_alloc_mod(alloc_mod: &Ident) -> Tok2 {
+    if !cfg!(feature = "alloc") {
+        return quote!();
+    }
+    quote!(
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        mod #alloc_mod {
+            extern crate alloc;
+            #[allow(unused_imports)]
+            pub use self::alloc::boxed::Box;
+        }
+    )
+}
+
+/// How the overloads declared by `overloadable`-style macros are made callable.
+#[derive(Clone, Copy)]
+enum Backend<'a> {
+    /// Implementing the `Fn*` traits, so that the struct can be called directly.
+    FnTraits,
+    /// Implementing the given dispatch trait, which the struct's inherent `call`
+    /// method forwards to. This works on stable.
+    Stable(&'a Ident),
+}
+
+/// The 32-bit FNV-1a hash of `s`, used where generated names need to be both
+/// deterministic and unlikely to collide.
+fn fnv1a(s: &str) -> u32 {
+    s.bytes()
+        .fold(0x811c_9dc5, |hash, byte| (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193))
+}
+
+/// An identifier-friendly rendering of an argument list, such as `usize_ref_str` for
+/// `(usize, &str)`.
+fn type_suffix(types: &[&Type]) -> String {
+    if types.is_empty() {
+        return String::from("unit");
+    }
+    tokens_suffix(quote!(#(#types),*))
+}
+
+/// The same rendering of any tokens, such as `u8` for `<u8>`.
+fn tokens_suffix(tokens: Tok2) -> String {
+    let mut suffix = String::new();
+    for c in tokens.to_string().chars() {
+        match c {
+            '&' => suffix.push_str("_ref_"),
+            '*' => suffix.push_str("_ptr_"),
+            c if c.is_alphanumeric() => suffix.extend(c.to_lowercase()),
+            _ => suffix.push('_'),
+        }
+    }
+    suffix
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+fn gen_fn_decls<T: IntoIterator<Item = ParsedFnDef>>(
+    fns: T,
+    name: &Ident,
+    vis: &Visibility,
+    backend: Backend,
+    dyn_name: Option<&Ident>,
+    stateful: bool,
+    extending: bool,
+) -> Result<Tok2> {
+    // Methods of the object-safe trait, if one is requested, and their impls.
+    let mut dyn_decls = Vec::new();
+    let mut dyn_methods = Vec::new();
+    // Calls of those methods for arguments and outputs given as `dyn Any`.
+    let mut dyn_dispatch = Vec::new();
+    let with_name = match backend {
+        // Whether the struct behind `with` was declared can't be known here.
+        Backend::FnTraits if extending => None,
+        Backend::FnTraits => Some(Ident::new(&format!("{}With", camel_case(&name.to_string())), name.span())),
+        // `call` can already be given the argument types with a turbofish.
+        Backend::Stable(_) => None,
+    };
+    let mut with_impls = Vec::new();
+    let mut any_generic = false;
+    // Names the never type on stable, where `FnOnce<()>` can't be written.
+    let never_name = match backend {
+        Backend::FnTraits => None,
+        Backend::Stable(_) => Some(Ident::new(&format!("{}Never", camel_case(&name.to_string())), name.span())),
+    };
+    let mut any_never = false;
+    // Normalized argument tuples of the overloads seen so far, and their return types.
+    let mut signatures = HashMap::new();
+    // The `#[cfg(...)]`s of overloads which are alternative bodies of one signature,
+    // keyed by their argument tuples, in order of appearance, with the argument
+    // types as they are shown in errors.
+    let mut alternatives: Vec<(String, Span, String, Vec<Tok2>)> = Vec::new();
+    // Suffixes of the function pointer accessors generated so far.
+    let mut fn_ptr_suffixes = HashSet::new();
+    let fns: Vec<ParsedFnDef> = fns
+        .into_iter()
+        .flat_map(ParsedFnDef::expand_repeat)
+        .flat_map(ParsedFnDef::expand_defaults)
+        .collect();
+    check_coerce_via_arity(&fns)?;
+    // Another invocation may already declare a module for the same name.
+    let alloc_mod = if extending {
+        let signatures: Vec<String> = fns.iter().map(|def| def.signature(name)).collect();
+        Ident::new(&format!("__{}_extended_{:08X}_alloc", name, fnv1a(&signatures.join(", "))), name.span())
+    } else {
+        Ident::new(&format!("__{}_alloc", name), name.span())
+    };
+    // Overloads without a priority have the lowest, and are emitted last.
+    let mut fns = fns;
+    let priority_meta = fns.iter().flat_map(|def| &def.meta).find(|(m, _)| m.name() == "overload_priority");
+    let prioritized = match priority_meta {
+        Some((m, _)) if stateful || extending => {
+            return Err(Error::new_spanned(
+                m,
+                "`#[overload_priority(...)]` is only supported by overload sets without fields, \
+                 declared by `overloadable` or `overloadable_stable`.",
+            ));
+        }
+        Some(_) => Some(prioritized_name(name)),
+        None => None,
+    };
+    fns.sort_by_key(|def| std::cmp::Reverse(overload_priority(&def.meta)));
+    let highest_priority = fns.iter().filter_map(|def| overload_priority(&def.meta)).max().unwrap_or(0);
+    let fns: Vec<Tok2> = merge_dispatch(fns)?
+        .into_iter()
+        .map(ParsedFnDef::bind_param_attrs)
+        .map(|def| def.replace_self_type(name))
+        .map(ParsedFnDef::name_elided_output)
+        .map(ParsedFnDef::coerce_via_into)
+        .map(ParsedFnDef::desugar_impl_trait)
+        .map(ParsedFnDef::inject_phantom)
+        .map(
+        |ParsedFnDef {
+             gen,
+             params,
+             ret,
+             w_clause,
+             code,
+             paren,
+             mut meta,
+             this,
+             constness,
+             asyncness,
+             unsafety,
+             abi,
+             vis: fn_vis,
+             trait_name,
+             coercions,
+             ..
+        }| {
+            if let Some((_, trait_name)) = trait_name {
+                return Err(Error::new(trait_name.span(), "Overloads can only be given a trait name in `overloadable_member`."));
+            }
+            if !matches!(fn_vis, Visibility::Inherited) {
+                return Err(Error::new_spanned(fn_vis, "Individual overloads cannot have a visibility in `overloadable`."));
+            }
+            if this.is_some() {
+                return Err(Error::new(paren.span, "This declaration cannot contain a `self`-style parameter."));
+            }
+            if let Some(constness) = constness {
+                return Err(Error::new(constness.span, "`const` overloads are only supported by `overloadable_member`."));
+            }
+            if let Some(abi) = abi {
+                match &abi.name {
+                    Some(name) if name.value() == "Rust" => {}
+                    _ => return Err(Error::new_spanned(abi, "Only the `\"Rust\"` ABI is supported by `overloadable`, since the `Fn*` traits use `\"rust-call\"`.")),
+                }
+            }
+            let ret = match ret {
+                ReturnType::Type(_, ty) => *ty,
+                ReturnType::Default => Type::Tuple(TypeTuple { paren_token: paren, elems: Punctuated::new() }),
+            };
+            // `!` can only be written as an `Output` type with `#![feature(never_type)]`,
+            // but it can be named through the return type of a function pointer.
+            let ret = match (ret, &never_name) {
+                (Type::Never(never), Some(never_name)) => {
+                    any_never = true;
+                    quote_spanned!(never.bang_token.spans[0] => <fn() -> ! as #never_name>::Output)
+                }
+                (Type::Never(never), None) => quote_spanned!(never.bang_token.spans[0] => <fn() -> ! as ::core::ops::FnOnce<()>>::Output),
+                (ret, _) => quote!(#ret),
+            };
+            let ret: Type = syn::parse2(ret)?;
+            // `impl Trait` can't name the `Output` type, but a boxed trait object can.
+            let (ret, code) = if take_meta_word(&mut meta, "boxed") {
+                match ret {
+                    Type::ImplTrait(TypeImplTrait { bounds, impl_token }) => match box_path(&alloc_mod) {
+                        // The body is bound first, since passing it straight to `Box::new`
+                        // would lint its braces as unused.
+                        Some(box_path) => (parse_quote!(#box_path<dyn #bounds>), parse_quote!({
+                            let __overloadable_value = #code;
+                            #box_path::new(__overloadable_value)
+                        })),
+                        None => {
+                            return Err(Error::new(impl_token.span, "`#[boxed]` overloads require the `alloc` or `std` feature of `overloadable`."));
+                        }
+                    },
+                    ret => return Err(Error::new_spanned(ret, "`#[boxed]` overloads must return an `impl Trait` type.")),
+                }
+            } else {
+                (ret, code)
+            };
+            let code = match unsafety {
+                Some(unsafety) => quote!({ #unsafety #code }),
+                None => quote!(#code),
+            };
+            let (ret, code) = match (asyncness, box_path(&alloc_mod)) {
+                (Some(_), Some(box_path)) => (
+                    quote!(::core::pin::Pin<#box_path<dyn ::core::future::Future<Output = #ret> + 'static>>),
+                    quote!({ #box_path::pin(async move #code) }),
+                ),
+                (Some(async_token), None) => {
+                    return Err(Error::new(async_token.span, "`async` overloads require the `alloc` or `std` feature of `overloadable`."));
+                }
+                (None, _) => (quote!(#ret), quote!(#code)),
+            };
+            let mut param_types = Vec::new();
+            let mut param_patterns = Vec::new();
+            params.iter().for_each(|(pat, _, ty)| {
+                param_types.push(ty);
+                param_patterns.push(pat)
+            });
+            let pty = &param_types[..];
+            let ppt = &param_patterns[..];
+            // The return type of an `Fn*` or stable impl can't constrain its
+            // parameters, so only the arguments count.
+            check_generics_used(&gen, &w_clause, quote!(#(#pty)*), "arguments")?;
+            let types = quote!(#(#pty,)*).to_string();
+            // Overloads with the same signature and their own `#[cfg(...)]`s are
+            // alternative bodies, of which exactly one has to be enabled.
+            let predicate = cfg_predicate(&meta);
+            let earlier = alternatives.iter_mut().find(|(earlier, ..)| *earlier == types);
+            let alternative = match (earlier, &predicate) {
+                (Some((.., predicates)), Some(predicate)) if signatures.get(&types) == Some(&ret.to_string()) => {
+                    // Should several be enabled, only the error below is reported,
+                    // rather than conflicting impls as well.
+                    let unless_earlier: Attribute = parse_quote!(#[cfg(not(any(#(#predicates),*)))]);
+                    meta.push((unless_earlier.parse_meta()?, Default::default()));
+                    predicates.push(predicate.clone());
+                    true
+                }
+                _ => false,
+            };
+            if let (false, Some(predicate)) = (alternative, &predicate) {
+                let shown: Vec<_> = pty.iter().map(|ty| ty.into_token_stream().to_string()).collect();
+                alternatives.push((types.clone(), paren.span, shown.join(", "), vec![predicate.clone()]));
+            }
+            if let (false, Some(earlier_ret)) = (alternative, signatures.insert(types, ret.to_string())) {
+                if earlier_ret == ret.to_string() {
+                    return Err(Error::new(paren.span, "This overload's argument types duplicate those of an earlier overload."));
+                }
+                return Err(Error::new_spanned(
+                    &ret,
+                    format!(
+                        "This overload only differs from an earlier one, returning `{}`, by its return type. \
+                         Overloading on the return type is impossible, since the output is determined by the argument types.",
+                        earlier_ret
+                    ),
+                ));
+            }
+            if let Some((m, _)) = meta.iter().find(|(m, _)| m.name() == "coerce_via") {
+                return Err(Error::new_spanned(m, "Expected `#[coerce_via(Into)]`, which is the only conversion supported."));
+            }
+            if let Some((m, _)) = meta.iter().find(|(m, _)| m.name() == "must_use") {
+                return Err(Error::new_spanned(m, "`#[must_use]` has no effect on the methods implementing an overload, since calls go through a trait. \
+                    Consider returning a `#[must_use]` type such as `Result`, or using `overloadable_member` instead."));
+            }
+            if let (Backend::FnTraits, Some((m, _))) = (backend, meta.iter().find(|(m, _)| m.name() == "track_caller")) {
+                return Err(Error::new_spanned(m, "`#[track_caller]` is not supported by `overloadable`, since the `Fn*` traits use the `\"rust-call\"` ABI. Consider using `overloadable_stable` instead."));
+            }
+            // Only applied to the signatures generated around the body, not the body itself.
+            let lint_allows = take_clippy_allows(&mut meta)?;
+            let priority = take_overload_priority(&mut meta)?.unwrap_or(0);
+            // Optimization hints are applied to every `Fn*` method, since any of them
+            // may be the entry point, whereas other attributes stay on `call`.
+            let (propagated, meta) = partition_propagated_meta(meta);
+            for (m, _) in &propagated {
+                for hint in cfg_attr_contents(m) {
+                    check_propagated_meta(hint)?;
+                }
+            }
+            // The `Fn*` methods all forward to one another, so they are worth inlining,
+            // unless the user has asked for something more specific, if only when some
+            // `#[cfg_attr]` applies.
+            let conditions: Vec<_> = propagated
+                .iter()
+                .filter(|(m, _)| cfg_attr_contents(m).iter().any(|hint| hint.name() == "inline"))
+                .map(|(m, _)| cfg_attr_predicate(m))
+                .collect();
+            let inline = if conditions.iter().any(Option::is_none) {
+                quote!()
+            } else if conditions.is_empty() {
+                quote!(#[inline])
+            } else {
+                quote!(#[cfg_attr(not(any(#(#conditions),*)), inline)])
+            };
+            let propagated = propagated.iter().map(|(m, b)| quote_spanned!(b.span => #[#m]));
+            let inline = quote!(#inline #(#propagated)*);
+            let SplitMeta { item: item_meta, method: meta, decl_only: deprecated, .. } = split_meta(&meta);
+            let item_meta = &item_meta[..];
+            let meta = &meta[..];
+            let deprecated = &deprecated[..];
+            // Lifetimes which aren't bounded anywhere are late-bound, so a function
+            // generic over only those still coerces to a higher-ranked pointer.
+            let late_bound: Option<Vec<&Lifetime>> = match &gen {
+                Some(gen) if w_clause.is_none() => gen
+                    .params
+                    .iter()
+                    .map(|param| match param {
+                        GenericParam::Lifetime(def) if def.bounds.is_empty() => Some(&def.lifetime),
+                        _ => None,
+                    })
+                    .collect(),
+                Some(_) => None,
+                None => Some(Vec::new()),
+            };
+            let generic = late_bound.is_none();
+            let binder = match &late_bound {
+                Some(lifetimes) if !lifetimes.is_empty() => quote!(for<#(#lifetimes),*>),
+                _ => quote!(),
+            };
+            let suffix = if !generic || !deprecated.is_empty() {
+                // The overload's own lifetimes are left out, being different for every call.
+                let mut unnamed: Vec<Type> = pty.iter().map(|&ty| ty.clone()).collect();
+                if let Some(names) = &late_bound {
+                    for ty in unnamed.iter_mut() {
+                        ReplaceLifetimes { names: Some(names), with: "'_" }.visit_type_mut(ty);
+                    }
+                }
+                let mut suffix = type_suffix(&unnamed.iter().collect::<Vec<_>>());
+                if !fn_ptr_suffixes.insert(suffix.clone()) && !alternative {
+                    suffix = format!("{}_{}", suffix, fn_ptr_suffixes.len());
+                    fn_ptr_suffixes.insert(suffix.clone());
+                }
+                suffix
+            } else {
+                String::new()
+            };
+            let args: Vec<Ident> = (0..pty.len()).map(|i| Ident::new(&format!("_{}", i), paren.span)).collect();
+            let args = &args[..];
+            let call = |this: Tok2| match backend {
+                Backend::FnTraits => quote!(::core::ops::Fn::call(#this, (#(#args,)*))),
+                Backend::Stable(trait_name) => quote!(#trait_name::call(#this, (#(#args,)*))),
+            };
+            // A call through the `Fn*` traits or `call` can't be linted as deprecated,
+            // since deprecation only applies to the paths naming an item, so the
+            // overload is also given a method of its own which can.
+            let deprecated_shim = if deprecated.is_empty() {
+                quote!()
+            } else {
+                let method = Ident::new(&format!("call_{}", suffix), paren.span);
+                let call = call(quote!(self));
+                quote!(
+                    #(#item_meta)*
+                    #[automatically_derived]
+                    impl #name {
+                        #(#deprecated)*
+                        #[allow(dead_code)]
+                        #lint_allows
+                        #vis fn #method#gen(&self, #(#args: #pty),*) -> #ret #w_clause {
+                            #call
+                        }
+                    }
+                )
+            };
+            // Non-generic overloads can be coerced to a plain function pointer
+            // through a monomorphic shim, as long as the struct has no state.
+            let fn_ptr = if !generic {
+                let accessor = Ident::new(&format!("as_fn_ptr_{}", suffix), paren.span);
+                if let Some(dyn_name) = dyn_name {
+                    let method = Ident::new(&format!("call_{}", suffix), paren.span);
+                    let call = call(quote!(self));
+                    dyn_decls.push(quote!(
+                        #(#item_meta)*
+                        #(#deprecated)*
+                        #lint_allows
+                        fn #method#gen(&self, #(#args: #pty),*) -> #ret;
+                    ));
+                    dyn_methods.push(quote!(
+                        #(#item_meta)*
+                        #[inline]
+                        fn #method#gen(&self, #(#args: #pty),*) -> #ret {
+                            #call
+                        }
+                    ));
+                    // Any elided lifetimes are inferred to be `'static`, as `Any` requires,
+                    // and any named ones are made so.
+                    let mut static_types: Vec<Type> = pty.iter().map(|&ty| ty.clone()).collect();
+                    static_types.push(syn::parse2(ret.clone())?);
+                    for ty in static_types.iter_mut() {
+                        ReplaceLifetimes { names: None, with: "'static" }.visit_type_mut(ty);
+                    }
+                    let static_ret = static_types.pop();
+                    dyn_dispatch.push(quote!(
+                        #(#item_meta)*
+                        {
+                            if let (::core::option::Option::Some(args), ::core::option::Option::Some(output)) = (
+                                args.downcast_mut::<::core::option::Option<(#(#static_types,)*)>>(),
+                                output.downcast_mut::<::core::option::Option<#static_ret>>(),
+                            ) {
+                                if let ::core::option::Option::Some((#(#args,)*)) = args.take() {
+                                    *output = ::core::option::Option::Some(#dyn_name::#method(self, #(#args),*));
+                                }
+                                return;
+                            }
+                        }
+                    ));
+                }
+                // `#[coercible_to(...)]` checks that the shim coerces to each type.
+                let mut checks = Vec::with_capacity(coercions.len());
+                for ty in &coercions {
+                    if stateful {
+                        return Err(Error::new_spanned(ty, "Overloads of a struct with fields cannot be coerced to function pointers."));
+                    }
+                    let call = call(quote!(&#name));
+                    // Only the coercion itself points at the type, should it fail.
+                    let shim = quote_spanned!(ty.span() => shim);
+                    checks.push(quote!(
+                        #(#item_meta)*
+                        const _: #ty = {
+                            #lint_allows
+                            fn shim#gen(#(#args: #pty),*) -> #ret {
+                                #call
+                            }
+                            #shim
+                        };
+                    ));
+                }
+                if stateful {
+                    quote!()
+                } else {
+                    let call = call(quote!(&#name));
+                    // Erases the overload as a trait object, which works alike on stable.
+                    let boxed = match box_path(&alloc_mod) {
+                        Some(box_path) => {
+                            let boxed = Ident::new(&format!("boxed_{}", suffix), paren.span);
+                            quote!(
+                                #(#deprecated)*
+                                #[allow(dead_code)]
+                                #lint_allows
+                                #vis fn #boxed(&self) -> #box_path<dyn #binder ::core::ops::Fn(#(#pty),*) -> #ret> {
+                                    #lint_allows
+                                    fn shim#gen(#(#args: #pty),*) -> #ret {
+                                        #call
+                                    }
+                                    #box_path::new(shim)
+                                }
+                            )
+                        }
+                        None => quote!(),
+                    };
+                    quote!(
+                        #(#checks)*
+                        #(#item_meta)*
+                        #[automatically_derived]
+                        impl #name {
+                            #(#deprecated)*
+                            #[allow(dead_code)]
+                            #lint_allows
+                            #vis const fn #accessor(&self) -> #binder fn(#(#pty),*) -> #ret {
+                                #[inline]
+                                #lint_allows
+                                fn shim#gen(#(#args: #pty),*) -> #ret {
+                                    #call
+                                }
+                                shim
+                            }
+                            #boxed
+                        }
+                    )
+                }
+            } else if let Some(ty) = coercions.first() {
+                return Err(Error::new_spanned(ty, "Only overloads without generic parameters can be coerced to function pointers."));
+            } else {
+                quote!()
+            };
+            // Points errors about the `Output`, such as it being less public than the
+            // struct, at the return type.
+            let output = quote_spanned!(ret.span() => type Output = #ret;);
+            let prioritized_impl = match &prioritized {
+                Some(prioritized_name) => {
+                    let trait_name = priority_trait_name(name, priority);
+                    let refs = (0..priority).map(|_| quote!(&));
+                    let call = call(quote!(f));
+                    quote!(
+                        #(#item_meta)*
+                        #[automatically_derived]
+                        impl#gen #trait_name for #(#refs)* #prioritized_name<(#(#pty,)*)> #w_clause {
+                            #output
+                            #inline
+                            #lint_allows
+                            fn __overloadable_call(&self, f: &#name) -> Self::Output {
+                                let (#(#args,)*) = ::core::option::Option::unwrap(self.0.take());
+                                #call
+                            }
+                        }
+                    )
+                }
+                None => quote!(),
+            };
+            if let Backend::Stable(trait_name) = backend {
+                return Ok(quote!(
+                    #fn_ptr
+                    #deprecated_shim
+                    #prioritized_impl
+                    #(#item_meta)*
+                    #[automatically_derived]
+                    impl#gen #trait_name<(#(#pty,)*)> for #name #w_clause {
+                        #output
+                        #inline
+                        #(#meta)*
+                        fn call(&self, (#(#ppt,)*): (#(#pty,)*)) -> Self::Output #code
+                    }
+                ));
+            }
+            if let Some(with_name) = &with_name {
+                let mut with_gen = gen.clone().unwrap_or_default();
+                any_generic |= !with_gen.params.is_empty();
+                with_gen.params.insert(0, parse_quote!('__overloadable));
+                with_impls.push(quote!(
+                    #(#item_meta)*
+                    #[automatically_derived]
+                    impl #with_gen ::core::ops::Fn<(#(#pty,)*)> for #with_name<'__overloadable, (#(#pty,)*)> #w_clause {
+                        #inline
+                        extern "rust-call" fn call(&self, x: (#(#pty,)*)) -> Self::Output {
+                            ::core::ops::Fn::call(self.0, x)
+                        }
+                    }
+                    #(#item_meta)*
+                    #[automatically_derived]
+                    impl #with_gen ::core::ops::FnOnce<(#(#pty,)*)> for #with_name<'__overloadable, (#(#pty,)*)> #w_clause {
+                        #output
+                        #inline
+                        extern "rust-call" fn call_once(self, x: (#(#pty,)*)) -> Self::Output {
+                            ::core::ops::Fn::call(self.0, x)
+                        }
+                    }
+                    #(#item_meta)*
+                    #[automatically_derived]
+                    impl #with_gen ::core::ops::FnMut<(#(#pty,)*)> for #with_name<'__overloadable, (#(#pty,)*)> #w_clause {
+                        #inline
+                        extern "rust-call" fn call_mut(&mut self, x: (#(#pty,)*)) -> Self::Output {
+                            ::core::ops::Fn::call(self.0, x)
+                        }
+                    }
+                ));
+            }
+            // `call_once` and `call_mut` borrow `self` for `call`, which is sound even for
+            // a struct with fields, since the `Output` can't borrow from `self`, which
+            // `call_once` then drops as it would any other owned value.
+            Ok(quote!(
+                #fn_ptr
+                #deprecated_shim
+                #prioritized_impl
+                #(#item_meta)*
+                #[automatically_derived]
+                impl#gen ::core::ops::Fn<(#(#pty,)*)> for #name #w_clause {
+                    #inline
+                    #(#meta)*
+                    extern "rust-call" fn call(&self, (#(#ppt,)*): (#(#pty,)*)) -> Self::Output #code
+                }
+                #(#item_meta)*
+                #[automatically_derived]
+                impl#gen ::core::ops::FnOnce<(#(#pty,)*)> for #name #w_clause {
+                    #output
+                    #inline
+                    extern "rust-call" fn call_once(self, x: (#(#pty,)*)) -> Self::Output {
+                        <Self as ::core::ops::Fn<(#(#pty,)*)>>::call(&self, x)
+                    }
+                }
+                #(#item_meta)*
+                #[automatically_derived]
+                impl#gen ::core::ops::FnMut<(#(#pty,)*)> for #name #w_clause {
+                    #inline
+                    extern "rust-call" fn call_mut(&mut self, x: (#(#pty,)*)) -> Self::Output {
+                        <Self as ::core::ops::Fn<(#(#pty,)*)>>::call(self, x)
+                    }
+                }
+            ))
+        }
+    ).collect::<Result<Vec<Tok2>>>()?;
+    if fns.is_empty() {
+        return Err(no_overloads(name));
+    }
+    let alternatives = alternatives.into_iter().filter(|(.., predicates)| predicates.len() > 1).map(|(_, span, shown, predicates)| {
+        let none = format!("None of the bodies of the overload of `{}` taking `({})` is enabled by its `#[cfg(...)]`.", name, shown);
+        let many = format!("More than one body of the overload of `{}` taking `({})` is enabled by its `#[cfg(...)]`.", name, shown);
+        let mut overlaps = Vec::new();
+        for (i, first) in predicates.iter().enumerate() {
+            for second in &predicates[i + 1..] {
+                overlaps.push(quote_spanned!(span => #[cfg(all(#first, #second))] ::core::compile_error!(#many);));
+            }
+        }
+        quote_spanned!(span =>
+            #[cfg(not(any(#(#predicates),*)))]
+            ::core::compile_error!(#none);
+            #(#overlaps)*
+        )
+    });
+    let alternatives: Vec<_> = alternatives.collect();
+    // Lets generic overloads be picked by their argument types, since the struct
+    // itself can't be given a turbofish.
+    let with = match with_name {
+        Some(with_name) if any_generic => quote!(
+            #[doc(hidden)]
+            #[allow(non_camel_case_types)]
+            #vis struct #with_name<'__overloadable, Args>(&'__overloadable #name, ::core::marker::PhantomData<fn(Args)>);
+            #[automatically_derived]
+            impl #name {
+                #[allow(dead_code)]
+                #vis fn with<Args>(&self) -> #with_name<'_, Args> {
+                    #with_name(self, ::core::marker::PhantomData)
+                }
+            }
+            #(#with_impls)*
+        ),
+        _ => quote!(),
+    };
+    let dyn_trait = match dyn_name {
+        Some(dyn_name) => {
+            let no_match = Ident::new(&format!("{}NoMatchError", camel_case(&name.to_string())), dyn_name.span());
+            let message = format!("No overload of `{}` takes `{{}}` and returns `{{}}`.", name);
+            let declarations = quote!(
+                #vis trait #dyn_name {
+                    #(#dyn_decls)*
+                    /// Calls the overload taking the arguments in `args`, an `Option` of
+                    /// their tuple, if it returns the type of the `Option` in `output`.
+                    #[doc(hidden)]
+                    fn __overloadable_dispatch(&self, args: &mut dyn ::core::any::Any, output: &mut dyn ::core::any::Any) {
+                        let _ = (args, output);
+                    }
+                }
+                #[automatically_derived]
+                impl #dyn_name for #name {
+                    #(#dyn_methods)*
+                    fn __overloadable_dispatch(&self, args: &mut dyn ::core::any::Any, output: &mut dyn ::core::any::Any) {
+                        #(#dyn_dispatch)*
+                        let _ = (args, output);
+                    }
+                }
+            );
+            let error = quote!(
+                /// The error of calling an overload which doesn't exist through `try_call`.
+                #[derive(::core::fmt::Debug, ::core::clone::Clone, ::core::marker::Copy, ::core::cmp::PartialEq, ::core::cmp::Eq)]
+                #vis struct #no_match {
+                    /// The type of the arguments' tuple.
+                    pub arguments: &'static str,
+                    /// The type that was expected to be returned.
+                    pub output: &'static str,
+                }
+                #[automatically_derived]
+                impl ::core::fmt::Display for #no_match {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        ::core::write!(f, #message, self.arguments, self.output)
+                    }
+                }
+                #[automatically_derived]
+                impl ::core::error::Error for #no_match {}
+            );
+            let calls = quote!(
+                #[automatically_derived]
+                impl dyn #dyn_name + '_ {
+                    /// Calls the overload taking the arguments in the tuple `args` and
+                    /// returning `R`, or returns an error if there is none.
+                    #[allow(dead_code)]
+                    #vis fn try_call<Args: ::core::any::Any, R: ::core::any::Any>(&self, args: Args) -> ::core::result::Result<R, #no_match> {
+                        let mut args = ::core::option::Option::Some(args);
+                        let mut output = ::core::option::Option::None::<R>;
+                        #dyn_name::__overloadable_dispatch(self, &mut args, &mut output);
+                        output.ok_or(#no_match {
+                            arguments: ::core::any::type_name::<Args>(),
+                            output: ::core::any::type_name::<R>(),
+                        })
+                    }
+
+                    /// Calls the overload taking the arguments in the tuple `args` and
+                    /// returning `R`, panicking if there is none.
+                    #[allow(dead_code)]
+                    #[track_caller]
+                    #vis fn call_any<Args: ::core::any::Any, R: ::core::any::Any>(&self, args: Args) -> R {
+                        match self.try_call(args) {
+                            ::core::result::Result::Ok(output) => output,
+                            ::core::result::Result::Err(error) => ::core::panic!("{}", error),
+                        }
+                    }
+                }
+            );
+            quote!(#declarations #error #calls)
+        }
+        None => quote!(),
+    };
+    // The features can't be detected here, but this at least shows up when looking
+    // at the expansion to find out why the impls below don't compile.
+    let guidance = match backend {
+        Backend::FnTraits => {
+            let note = format!(
+                "`{}` implements the `Fn*` traits, which requires `#![feature(unboxed_closures, fn_traits)]` \
+                 at the root of the crate invoking `overloadable`, or using `overloadable_stable` instead.",
+                name
+            );
+            quote!(
+                #[doc = #note]
+                const _: () = ();
+            )
+        }
+        Backend::Stable(_) => quote!(),
+    };
+    let alloc_mod_decl = gen_alloc_mod(&alloc_mod);
+    let never = match never_name {
+        Some(never_name) if any_never => quote!(
+            #[doc(hidden)]
+            #vis trait #never_name {
+                type Output;
+            }
+            #[automatically_derived]
+            impl<T> #never_name for fn() -> T {
+                type Output = T;
+            }
+        ),
+        _ => quote!(),
+    };
+    // One trait per priority, all with the same method, so that whichever is tried
+    // first by method resolution is picked.
+    let priorities = match prioritized {
+        Some(prioritized_name) => {
+            let traits: Vec<Tok2> = (0..=highest_priority)
+                .map(|priority| {
+                    let trait_name = priority_trait_name(name, priority);
+                    quote!(
+                        #[doc(hidden)]
+                        #vis trait #trait_name {
+                            type Output;
+                            fn __overloadable_call(&self, f: &#name) -> Self::Output;
+                        }
+                    )
+                })
+                .collect();
+            quote!(
+                #[doc(hidden)]
+                #vis struct #prioritized_name<Args>(pub ::core::cell::Cell<::core::option::Option<Args>>);
+                #(#traits)*
+            )
+        }
+        None => quote!(),
+    };
+    Ok(quote!(
+        #guidance
+        #alloc_mod_decl
+        #never
+        #(#alternatives)*
+        #(#fns)*
+        #with
+        #dyn_trait
+        #priorities
+    ))
+}
+
+/// The error for an invocation without any overloads, which is most likely a mistake
+/// in whatever generated it.
+fn no_overloads(name: &Ident) -> Error {
+    Error::new(name.span(), format!("`{}` must have at least one overload.", name))
+}
+
+/// A member overload, lowered to the pieces of its trait declaration and impl.
+struct MemberFn {
+    item_meta: Vec<Tok2>,
+    decl_meta: Vec<Tok2>,
+    decl_only_meta: Vec<Tok2>,
+    meta: Vec<Tok2>,
+    vis: Visibility,
+    trait_name: Ident,
+    trait_gen: Generics,
+    sized: bool,
+    constness: Option<Token![const]>,
+    qualifiers: Tok2,
+    gen: Option<Generics>,
+    this: Option<ThisDef>,
+    trait_params: Vec<Tok2>,
+    impl_params: Vec<Tok2>,
+    args: Vec<Ident>,
+    ret: Type,
+    w_clause: Option<WhereClause>,
+    /// The struct's generics, along with any of the overload's predicates which
+    /// only concern them, for the impl header.
+    impl_gen: Generics,
+    code: Block,
+    /// Distinguishes this overload's method in a grouped trait.
+    suffix: String,
+}
+
+impl MemberFn {
+    fn decl(&self, name: &Ident) -> Tok2 {
+        let MemberFn {
+            decl_meta,
+            decl_only_meta,
+            qualifiers,
+            gen,
+            this,
+            trait_params,
+            ret,
+            w_clause,
+            ..
+        } = self;
+        let this = this.as_ref().map(ThisDef::decl_tokens);
+        quote!(
+            #(#decl_meta)*
+            #(#decl_only_meta)*
+            #qualifiers fn #name#gen(#this#(#trait_params),*) -> #ret #w_clause;
+        )
+    }
+
+    fn method(&self, name: &Ident) -> Tok2 {
+        let MemberFn {
+            meta,
+            qualifiers,
+            gen,
+            this,
+            impl_params,
+            ret,
+            w_clause,
+            code,
+            ..
+        } = self;
+        quote!(
+            #(#meta)*
+            #qualifiers fn #name#gen(#this#(#impl_params),*) -> #ret #w_clause #code
+        )
+    }
+
+    /// A method named `name` which calls `target` with the same arguments.
+    fn forward(&self, vis: &Visibility, name: &Ident, target: Tok2) -> Tok2 {
+        let MemberFn {
+            decl_meta,
+            constness,
+            qualifiers,
+            gen,
+            this,
+            trait_params,
+            args,
+            ret,
+            w_clause,
+            ..
+        } = self;
+        let this_decl = this.as_ref().map(ThisDef::decl_tokens);
+        let this_arg = this.as_ref().map(|_| quote!(self,));
+        let await_token = if self.is_async() { quote!(.await) } else { quote!() };
+        quote!(
+            #(#decl_meta)*
+            #vis #constness #qualifiers fn #name#gen(#this_decl#(#trait_params),*) -> #ret #w_clause {
+                #target(#this_arg #(#args),*)#await_token
+            }
+        )
+    }
+
+    fn is_async(&self) -> bool {
+        self.qualifiers
+            .clone()
+            .into_iter()
+            .any(|tree| matches!(tree, TokenTree::Ident(ref ident) if ident == "async"))
+    }
+}
+
+/// Moves the predicates of an overload's `where` clause which only concern the
+/// struct's generics, and not the overload's own, into the returned impl generics.
+fn split_where_clause(
+    w_clause: Option<WhereClause>,
+    gen: &Option<Generics>,
+    struct_gen: &Generics,
+) -> (Option<WhereClause>, Generics) {
+    let mut impl_gen = struct_gen.clone();
+    let mut w_clause = match w_clause {
+        Some(w_clause) => w_clause,
+        None => return (None, impl_gen),
+    };
+    let mut method_idents = HashSet::new();
+    if let Some(gen) = gen {
+        collect_idents(quote!(#gen), &mut method_idents);
+    }
+    let mut struct_idents = HashSet::new();
+    collect_idents(quote!(#struct_gen), &mut struct_idents);
+    let mut method_predicates = Punctuated::new();
+    for predicate in w_clause.predicates {
+        let mut idents = HashSet::new();
+        collect_idents(quote!(#predicate), &mut idents);
+        if idents.is_disjoint(&method_idents) && !idents.is_disjoint(&struct_idents) {
+            impl_gen.make_where_clause().predicates.push(predicate);
+        } else {
+            method_predicates.push(predicate);
+        }
+    }
+    if method_predicates.is_empty() {
+        return (None, impl_gen);
+    }
+    w_clause.predicates = method_predicates;
+    (Some(w_clause), impl_gen)
+}
+
+/// Errors on lifetimes elided in `ret` which neither the receiver nor a single
+/// lifetime among the arguments could fill in, since the compiler would otherwise
+/// report them for both the trait declaration and the impl.
+fn check_output_elision(this: &Option<ThisDef>, params: &Punctuated<(Pat, Token![:], Type), Token![,]>, ret: &Type) -> Result<()> {
+    let mut output = ElidedLifetimes::default();
+    output.visit_type_mut(&mut ret.clone());
+    if output.elided == 0 {
+        return Ok(());
+    }
+    let mut inputs = ElidedLifetimes::default();
+    match this {
+        Some(ThisDef::Implicit(Some(_), ..)) => return Ok(()),
+        Some(ThisDef::Explicit(.., ty, _)) => inputs.visit_type_mut(&mut ty.clone()),
+        _ => {}
+    }
+    if inputs.elided + inputs.named.len() > 0 {
+        return Ok(());
+    }
+    for (_, _, ty) in params {
+        inputs.visit_type_mut(&mut ty.clone());
+    }
+    if inputs.elided + inputs.named.len() == 1 {
+        return Ok(());
+    }
+    Err(Error::new_spanned(
+        ret,
+        "Missing lifetime specifier: the return type borrows a value, but there is no receiver or single \
+         argument lifetime for it to borrow from. Consider `'static`, or returning an owned value instead.",
+    ))
+}
+
+fn gen_member_fn(
+    index: usize,
+    def: ParsedFnDef,
+    name: &Ident,
+    struct_name: &Ident,
+    struct_gen: &Generics,
+    vis: &Visibility,
+    grouped: bool,
+) -> Result<MemberFn> {
+    // Hashing the whole signature keeps separate invocations on the same struct from
+    // declaring traits of the same name, while keeping the names deterministic.
+    let this_tokens = &def.this;
+    let signature = format!("{} {}", quote!(#this_tokens), def.signature(name));
+    let ParsedFnDef {
+        gen,
+        params,
+        ret,
+        w_clause,
+        code,
+        meta,
+        this,
+        constness,
+        asyncness,
+        unsafety,
+        abi,
+        vis: fn_vis,
+        trait_name,
+        defaults,
+        param_attrs,
+        coercions,
+        examples,
+        repeat,
+        ..
+    } = def;
+    if let Some(repeat) = repeat {
+        return Err(Error::new(
+            repeat.ident.span(),
+            "`repeat` is only supported by `overloadable` and `overloadable_stable`, \
+             since member overloads are only told apart by their receivers.",
+        ));
+    }
+    if let Some(ty) = coercions.first() {
+        return Err(Error::new_spanned(
+            ty,
+            "`#[coercible_to(...)]` is only supported by `overloadable` and `overloadable_stable`.",
+        ));
+    }
+    if let Some(example) = examples.first() {
+        return Err(Error::new_spanned(
+            example,
+            "`#[example(...)]` is only supported by `overloadable`, `overloadable_stable`, `overloadable_for`, \
+             `overloadable_extend` and `#[overloads]` modules.",
+        ));
+    }
+    if let Some((m, _)) = meta.iter().find(|(m, _)| m.name() == "dispatch") {
+        return Err(Error::new_spanned(
+            m,
+            "`#[dispatch]` is not supported by `overloadable_member`, since member overloads are only told apart by their receivers.",
+        ));
+    }
+    if let Some((m, _)) = meta.iter().find(|(m, _)| m.name() == "overload_priority") {
+        return Err(Error::new_spanned(
+            m,
+            "`#[overload_priority(...)]` is not supported by `overloadable_member`, since method calls already prefer the receiver needing the fewest references.",
+        ));
+    }
+    if let Some(default) = defaults.iter().flatten().next() {
+        return Err(Error::new_spanned(
+            default,
+            "Default arguments are only supported by `overloadable` and `overloadable_stable`, \
+             since member overloads are only told apart by their receivers.",
+        ));
+    }
+    if let (Some(constness), Some(_)) = (constness, &this) {
+        return Err(Error::new(
+            constness.span,
+            "`const` overloads cannot take a `self`-style parameter.",
+        ));
+    }
+    // Generics may appear within the receiver's type, as `P` in `self: Pin<P>`, but
+    // the compiler rejects a receiver whose type is just one of them.
+    if let (Some(ThisDef::Explicit(_, _, _, Type::Path(TypePath { qself: None, path }), _)), Some(gen)) = (&this, &gen) {
+        if let Some(param) = gen.type_params().find(|param| path.is_ident(param.ident.clone())) {
+            return Err(Error::new_spanned(
+                path,
+                format!(
+                    "The type of `self` cannot be the generic parameter `{}`. Consider wrapping `Self` in it instead, \
+                     as in `self: Pin<P>` where `P: Deref<Target = Self>`, or taking it as a regular parameter.",
+                    param.ident
+                ),
+            ));
+        }
+    }
+    let ret = match ret {
+        ReturnType::Type(_, ty) => *ty,
+        // Spanned as generated code, so that clippy doesn't suggest removing it.
+        ReturnType::Default => parse_quote!(()),
+    };
+    check_output_elision(&this, &params, &ret)?;
+    let param_types: Vec<_> = params.iter().map(|(_, _, ty)| ty).collect();
+    let param_types = &param_types[..];
+    check_generics_used(&gen, &w_clause, quote!(#this #(#param_types)* #ret), "signature")?;
+    // A grouped trait's single impl can't be gated per overload, so the bounds
+    // stay on the methods there.
+    let (w_clause, impl_gen) = if grouped {
+        (w_clause, struct_gen.clone())
+    } else {
+        split_where_clause(w_clause, &gen, struct_gen)
+    };
+    // The trait only needs those of the struct's generics which its
+    // signature mentions; the rest are only seen by the impl.
+    let trait_gen = used_generics(struct_gen, quote!(#this #(#param_types)* #ret #w_clause));
+    let mut trait_params = Vec::with_capacity(params.len());
+    let mut impl_params = Vec::with_capacity(params.len());
+    let mut args = Vec::with_capacity(params.len());
+    for (index, ((lhs, _, rhs), attrs)) in params.iter().zip(&param_attrs).enumerate() {
+        let next_ident = Ident::new(&format!("_{}", index), lhs.span());
+        trait_params.push(quote!(#next_ident: #rhs));
+        impl_params.push(quote!(#(#attrs)* #lhs: #rhs));
+        args.push(next_ident);
+    }
+    let mut meta = meta;
+    let lint_allows = take_clippy_allows(&mut meta)?;
+    // By default, only a by-value `self` requires `Self: Sized`.
+    let sized = match (take_meta_word(&mut meta, "sized"), take_meta_word(&mut meta, "maybe_unsized")) {
+        (false, false) => ThisDef::is_sized_dependent(&this),
+        (true, false) => true,
+        (false, true) => false,
+        (true, true) => {
+            return Err(Error::new(
+                code.brace_token.span,
+                "An overload cannot be both `#[sized]` and `#[maybe_unsized]`.",
+            ));
+        }
+    };
+    let SplitMeta {
+        item: item_meta,
+        decl: decl_meta,
+        decl_only: mut decl_only_meta,
+        method: meta,
+    } = split_meta(&meta);
+    // The declaration is all generated, unlike the method with its body.
+    decl_only_meta.push(lint_allows);
+    let trait_name = match trait_name {
+        Some((_, trait_name)) => trait_name,
+        None => Ident::new(
+            &format!(
+                "{}Trait{}{:08X}",
+                struct_name,
+                index,
+                fnv1a(&signature)
+            ),
+            struct_name.span(),
+        ),
+    };
+    let this_decl = this.as_ref().map(ThisDef::decl_tokens);
+    let suffix = match (&this_decl, param_types.is_empty()) {
+        (None, true) => String::from("unit"),
+        (None, false) => type_suffix(param_types),
+        (Some(this_decl), true) => ident_suffix(this_decl.clone()),
+        (Some(this_decl), false) => format!(
+            "{}_{}",
+            ident_suffix(this_decl.clone()),
+            type_suffix(param_types)
+        ),
+    };
+    Ok(MemberFn {
+        item_meta,
+        decl_meta,
+        decl_only_meta,
+        meta,
+        vis: match fn_vis {
+            Visibility::Inherited => vis.clone(),
+            fn_vis => fn_vis,
+        },
+        trait_name,
+        trait_gen,
+        sized,
+        constness,
+        qualifiers: quote!(#asyncness #unsafety #abi),
+        gen,
+        this,
+        trait_params,
+        impl_params,
+        args,
+        ret,
+        w_clause,
+        impl_gen,
+        code,
+        suffix,
+    })
+}
+
+fn gen_trait_fn_decls<T: IntoIterator<Item = ParsedFnDef>>(
+    fns: T,
+    name: &Ident,
+    struct_names: &[Ident],
+    struct_gen: &Generics,
+    vis: &Visibility,
+    grouped: bool,
+    inherent: Option<Span>,
+) -> Result<Tok2> {
+    // The traits are named after the first struct, and implemented for all of them.
+    let struct_name = &struct_names[0];
+    let fns: Vec<MemberFn> = fns
+        .into_iter()
+        .map(ParsedFnDef::desugar_impl_trait)
+        .map(ParsedFnDef::inject_phantom)
+        .enumerate()
+        .map(|(index, def)| gen_member_fn(index, def, name, struct_name, struct_gen, vis, grouped))
+        .collect::<Result<_>>()?;
+    if fns.is_empty() {
+        return Err(no_overloads(name));
+    }
+
+    let associated = match inherent {
+        Some(span) => gen_associated_forwards(&fns, name, struct_names, span)?,
+        None => quote!(),
+    };
+    if grouped {
+        let decls = gen_grouped_trait_fn_decls(fns, name, struct_names, struct_gen, vis)?;
+        return Ok(quote!(
+            #decls
+            #associated
+        ));
+    }
+
+    let fns = fns.iter().map(|member_fn| {
+        let MemberFn {
+            item_meta,
+            vis,
+            trait_name,
+            trait_gen,
+            sized,
+            constness,
+            impl_gen,
+            ..
+        } = member_fn;
+        let (_, trait_ty_generics, _) = trait_gen.split_for_impl();
+        let (impl_generics, ty_generics, where_clause) = impl_gen.split_for_impl();
+        let sized_requirement = if *sized { quote!(::core::marker::Sized) } else { quote!() };
+        let impl_constness = constness.map(|_| quote!(const));
+        let decl = member_fn.decl(name);
+        let method = member_fn.method(name);
+        let impls = struct_names.iter().map(|struct_name| quote!(
+            #(#item_meta)*
+            #[automatically_derived]
+            impl #impl_generics #impl_constness #trait_name #trait_ty_generics for #struct_name #ty_generics #where_clause {
+                #method
+            }
+        ));
+        quote!(
+            #(#item_meta)*
+            #vis #constness trait #trait_name #trait_gen: #sized_requirement {
+                #decl
+            }
+            #(#impls)*
+        )
+    });
+
+    Ok(quote!(
+        #(#fns)*
+        #associated
+    ))
+}
+
+/// Handles `inherent` by declaring an inherent associated function forwarding to the
+/// only overload without a receiver, since `MyStruct::name(...)` would otherwise be
+/// ambiguous between the traits. Method calls skip associated functions, so
+/// `my_struct.name(...)` still finds the methods. This is opt-in, as inherent impls
+/// can only be declared for types of the same crate.
+fn gen_associated_forwards(fns: &[MemberFn], name: &Ident, struct_names: &[Ident], span: Span) -> Result<Tok2> {
+    let mut associated = fns.iter().filter(|member_fn| member_fn.this.is_none());
+    let member_fn = match (associated.next(), associated.next()) {
+        (Some(member_fn), None) => member_fn,
+        _ => {
+            return Err(Error::new(
+                span,
+                "`inherent` requires exactly one overload without a receiver, which is the one the inherent function forwards to.",
+            ));
+        }
+    };
+    let MemberFn {
+        item_meta,
+        vis,
+        trait_name,
+        trait_gen,
+        impl_gen,
+        ..
+    } = member_fn;
+    let (_, trait_ty_generics, _) = trait_gen.split_for_impl();
+    let (impl_generics, ty_generics, where_clause) = impl_gen.split_for_impl();
+    let forward = member_fn.forward(vis, name, quote!(<Self as #trait_name #trait_ty_generics>::#name));
+    let impls = struct_names.iter().map(|struct_name| quote!(
+        #(#item_meta)*
+        #[automatically_derived]
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            #[inline]
+            #forward
+        }
+    ));
+    Ok(quote!(#(#impls)*))
+}
+
+/// Emits a single trait holding every overload under a distinct name, along with
+/// the usual per-overload traits, which forward to it under the overloaded name.
+fn gen_grouped_trait_fn_decls(
+    fns: Vec<MemberFn>,
+    name: &Ident,
+    struct_names: &[Ident],
+    struct_gen: &Generics,
+    vis: &Visibility,
+) -> Result<Tok2> {
+    let struct_name = &struct_names[0];
+    if let Some(constness) = fns.iter().filter_map(|member_fn| member_fn.constness).next() {
+        return Err(Error::new(
+            constness.span,
+            "`const` overloads cannot be grouped into a single trait.",
+        ));
+    }
+    let (impl_generics, ty_generics, where_clause) = struct_gen.split_for_impl();
+    let group_name = Ident::new(
+        &format!("{}{}", struct_name, camel_case(&name.to_string())),
+        name.span(),
+    );
+    let mut suffixes = HashSet::new();
+    let grouped_names: Vec<Ident> = fns
+        .iter()
+        .map(|member_fn| {
+            let mut suffix = member_fn.suffix.clone();
+            if !suffixes.insert(suffix.clone()) {
+                suffix = format!("{}_{}", suffix, suffixes.len());
+                suffixes.insert(suffix.clone());
+            }
+            Ident::new(&format!("{}_{}", name, suffix), name.span())
+        })
+        .collect();
+    let decls: Vec<_> = fns
+        .iter()
+        .zip(&grouped_names)
+        .map(|(member_fn, grouped_name)| {
+            let item_meta = &member_fn.item_meta;
+            let decl = member_fn.decl(grouped_name);
+            quote!(#(#item_meta)* #decl)
+        })
+        .collect();
+    let group_gen = {
+        let decls = &decls[..];
+        used_generics(struct_gen, quote!(#(#decls)*))
+    };
+    let (_, group_ty_generics, _) = group_gen.split_for_impl();
+    let group_sized = if fns.iter().any(|member_fn| member_fn.sized) {
+        quote!(::core::marker::Sized)
+    } else {
+        quote!()
+    };
+    let methods: Vec<_> = fns
+        .iter()
+        .zip(&grouped_names)
+        .map(|(member_fn, grouped_name)| {
+            let item_meta = &member_fn.item_meta;
+            let method = member_fn.method(grouped_name);
+            quote!(#(#item_meta)* #method)
+        })
+        .collect();
+    let methods = &methods[..];
+    let group_impls = struct_names.iter().map(|struct_name| quote!(
+        #[automatically_derived]
+        impl #impl_generics #group_name #group_ty_generics for #struct_name #ty_generics #where_clause {
+            #(#methods)*
+        }
+    ));
+    let forwards = fns.iter().zip(&grouped_names).map(|(member_fn, grouped_name)| {
+        let MemberFn {
+            item_meta,
+            vis,
+            trait_name,
+            trait_gen,
+            sized,
+            ..
+        } = member_fn;
+        let (_, trait_ty_generics, _) = trait_gen.split_for_impl();
+        let sized_requirement = if *sized { quote!(::core::marker::Sized) } else { quote!() };
+        let decl = member_fn.decl(name);
+        let forward = member_fn.forward(
+            &Visibility::Inherited,
+            name,
+            quote!(<Self as #group_name #group_ty_generics>::#grouped_name),
+        );
+        let impls = struct_names.iter().map(|struct_name| quote!(
+            #(#item_meta)*
+            #[automatically_derived]
+            impl #impl_generics #trait_name #trait_ty_generics for #struct_name #ty_generics #where_clause {
+                #forward
+            }
+        ));
+        quote!(
+            #(#item_meta)*
+            #vis trait #trait_name #trait_gen: #sized_requirement {
+                #decl
+            }
+            #(#impls)*
+        )
+    });
+    Ok(quote!(
+        #vis trait #group_name #group_gen: #group_sized {
+            #(#decls)*
+        }
+        #(#group_impls)*
+        #(#forwards)*
+    ))
+}
+
+/// Converts a `snake_case` function name into the `CamelCase` used for trait names.
+fn camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Makes an identifier-safe string out of a receiver such as `&'a mut self`.
+fn ident_suffix(tokens: Tok2) -> String {
+    let mut words = Vec::new();
+    let mut lifetime = false;
+    for tree in tokens {
+        match tree {
+            TokenTree::Punct(ref punct) if punct.as_char() == '&' => words.push(String::from("ref")),
+            TokenTree::Punct(ref punct) if punct.as_char() == '\'' => lifetime = true,
+            TokenTree::Ident(_) if lifetime => lifetime = false,
+            TokenTree::Ident(ident) => words.push(ident.to_string().to_lowercase()),
+            _ => {}
+        }
+    }
+    words.join("_")
+}
+
+/// Expands `overloadable!`, given the tokens within it.
+pub fn overloadable(input: Tok2) -> Tok2 {
+    match syn::parse2::<OverloadableGlobal>(input) {
+        Ok(parsed) => gen_global_set(parsed),
+        Err(e) => e.to_compile_error(),
+    }
+}
+
+fn gen_global_set(parsed: OverloadableGlobal) -> Tok2 {
+    let OverloadableGlobal {
+        attrs,
+        callable,
+        dynamic,
+        documented,
+        vis,
+        name,
+        fields,
+        fns,
+        ..
+    } = parsed;
+    let fields = fields.map(|(_, fields)| fields);
+    let fns: Vec<_> = fns.into_iter().collect();
+    let summary = documented.map(|_| overload_summary(&name, &fns));
+    let mut attrs = attrs;
+    let (struct_name, callable_const) = match gen_callable_const(&mut attrs, &vis, &name, fields.as_ref()) {
+        Ok(renamed) => renamed,
+        Err(e) => return e.to_compile_error(),
+    };
+    let instance_const = gen_instance_const(&mut attrs, &vis, &name, &struct_name, fields.as_ref()).unwrap_or_else(|e| e.to_compile_error());
+    let signatures = if take_attr_word(&mut attrs, "signatures") {
+        gen_signatures(&vis, &struct_name, &name, &fns)
+    } else {
+        quote!()
+    };
+    let deref_warnings = if take_attr_word(&mut attrs, "warn_deref_overlap") {
+        gen_deref_warnings(&fns)
+    } else {
+        quote!()
+    };
+    let examples = gen_examples(&name, &fns, &format!("__{}", name));
+    let priority_macro = gen_priority_macro(&name, &struct_name, &fns);
+    let name = &struct_name;
+    let struct_decl = gen_struct_decl(&attrs, callable.is_some(), summary.as_deref(), &vis, name, fields.as_ref());
+    // Still declare the struct on error, so that uses of it don't add to the noise.
+    let dyn_name = dynamic.map(|_| dyn_trait_name(name));
+    let fn_decls =
+        gen_fn_decls(fns, name, &vis, Backend::FnTraits, dyn_name.as_ref(), fields.is_some(), false).unwrap_or_else(|e| e.to_compile_error());
+
+    let expanded = quote! {
+        #struct_decl
+        #callable_const
+        #instance_const
+        #signatures
+        #deref_warnings
+        #examples
+        #priority_macro
+        #(#fn_decls)*
+    };
+    expanded
+}
+
+fn gen_struct_decl(
+    attrs: &[Attribute],
+    callable: bool,
+    summary: Option<&str>,
+    vis: &Visibility,
+    name: &Ident,
+    fields: Option<&Punctuated<Field, Token![,]>>,
+) -> Tok2 {
+    let marker = if callable {
+        let marker_name = Ident::new(&format!("{}Callable", camel_case(&name.to_string())), name.span());
+        quote_spanned! { name.span() =>
+            #vis trait #marker_name {}
+            impl #marker_name for #name {}
+        }
+    } else {
+        quote!()
+    };
+    // A documented struct is shown as is, with the overloads listed after its own docs.
+    let (hidden, summary) = match summary {
+        Some(summary) => (quote!(), quote!(#[doc = #summary])),
+        None => (quote!(#[doc(hidden)]), quote!()),
+    };
+    // Aliases of a hidden struct would never come up in a search.
+    let alias_error = match doc_alias(attrs) {
+        Some(alias) if summary.is_empty() && fields.is_none() => Error::new_spanned(
+            alias,
+            "Doc aliases have no effect on a hidden overload set. Prefix its name with `documented` to show it.",
+        )
+        .to_compile_error(),
+        _ => quote!(),
+    };
+    // The layout of a transparent struct is that of its field, so it needs one.
+    let repr_error = match repr_transparent(attrs) {
+        Some(repr) if fields.map_or(0, Punctuated::len) != 1 => Error::new_spanned(
+            repr,
+            "`#[repr(transparent)]` requires the overload set to have exactly one field, as in `my_func { inner: u32 } as ...`.",
+        )
+        .to_compile_error(),
+        _ => quote!(),
+    };
+    // Only a struct named after the callable needs the lint to be silenced.
+    let camel_case_allow = if *name == camel_case(&name.to_string()) {
+        quote!()
+    } else {
+        quote!(#[allow(non_camel_case_types)])
+    };
+    let fields = match fields {
+        Some(fields) => fields,
+        None => {
+            let default = if derives_default(attrs) {
+                quote!()
+            } else {
+                quote_spanned! { name.span() =>
+                    impl ::core::default::Default for #name {
+                        fn default() -> Self {
+                            #name
+                        }
+                    }
+                }
+            };
+            return quote_spanned! { name.span() =>
+                #hidden
+                #camel_case_allow
+                #[allow(dead_code)]
+                #(#attrs)*
+                #summary
+                #vis struct #name;
+                #default
+                impl #name {
+                    #[allow(dead_code)]
+                    #vis const fn new() -> Self {
+                        #name
+                    }
+                }
+                #marker
+                #alias_error
+                #repr_error
+            };
+        }
+    };
+    let idents = fields.iter().map(|field| &field.ident);
+    let params = fields.iter().map(|Field { ident, ty, .. }| quote!(#ident: #ty));
+    quote_spanned! { name.span() =>
+        #camel_case_allow
+        #(#attrs)*
+        #summary
+        #vis struct #name {
+            #fields
+        }
+        impl #name {
+            #[allow(dead_code)]
+            #[allow(clippy::too_many_arguments)]
+            #vis const fn new(#(#params),*) -> Self {
+                #name { #(#idents),* }
+            }
+        }
+        #marker
+        #repr_error
+    }
+}
+
+/// A markdown list of the signatures of `fns`, each followed by its own docs.
+fn overload_summary(name: &Ident, fns: &[ParsedFnDef]) -> String {
+    let mut summary = String::from("\n# Overloads\n");
+    for def in fns.iter().cloned().flat_map(ParsedFnDef::expand_repeat).flat_map(ParsedFnDef::expand_defaults) {
+        summary.push_str(&format!("\n- `{}`\n", def.signature(name)));
+        let docs = def.meta.iter().filter_map(|(meta, _)| match meta {
+            Meta::NameValue(MetaNameValue { ident, lit: Lit::Str(doc), .. }) if ident == "doc" => Some(doc.value()),
+            _ => None,
+        });
+        let mut docs = docs.peekable();
+        if docs.peek().is_some() {
+            summary.push('\n');
+        }
+        for doc in docs {
+            summary.push_str(&format!("  {}\n", doc.trim_end()));
+        }
+    }
+    summary
+}
+
+/// Renders `tokens` roughly as they would be written by hand, rather than with a
+/// space between every token.
+fn tidy_tokens(tokens: Tok2) -> String {
+    let mut tidy = tokens.to_string();
+    for &(spaced, tight) in &[
+        (" :: ", "::"),
+        (" : ", ": "),
+        (" , ", ", "),
+        (" ;", ";"),
+        ("& ", "&"),
+        (" < ", "<"),
+        (" <", "<"),
+        ("< ", "<"),
+        (" >", ">"),
+        ("( ", "("),
+        (" )", ")"),
+        ("[ ", "["),
+        (" ]", "]"),
+        (" ,", ","),
+        (" ?", "?"),
+    ] {
+        tidy = tidy.replace(spaced, tight);
+    }
+    // Calls and `Fn(...)` bounds, as opposed to tuples following a `:` or `->`.
+    let mut parts = tidy.split(" (");
+    let mut joined = String::from(parts.next().unwrap_or_default());
+    for part in parts {
+        let previous = joined.rsplit(|c: char| !c.is_alphanumeric() && c != '_').next().unwrap_or_default();
+        let glued = if joined.ends_with('>') {
+            !joined.ends_with("->")
+        } else {
+            !previous.is_empty() && !["mut", "dyn", "impl", "where"].contains(&previous)
+        };
+        joined.push_str(if glued { "(" } else { " (" });
+        joined.push_str(part);
+    }
+    joined
+}
+
+/// The name of the object-safe trait declared for an overload set by `dyn`.
+fn dyn_trait_name(name: &Ident) -> Ident {
+    Ident::new(&format!("{}Dyn", camel_case(&name.to_string())), name.span())
+}
+
+/// Removes a `#[name]` attribute from `attrs`, returning whether it was present.
+fn take_attr_word(attrs: &mut Vec<Attribute>, name: &str) -> bool {
+    let len = attrs.len();
+    attrs.retain(|attr| !(attr.path.is_ident(name) && attr.tts.is_empty()));
+    attrs.len() != len
+}
+
+/// Declares a test asserting each `#[example(...)]` of `fns`, named `{prefix}_example_N`.
+/// They are declared at the call site rather than in a module of their own, so that
+/// the overloads they call are in scope wherever the set is declared.
+fn gen_examples(name: &Ident, fns: &[ParsedFnDef], prefix: &str) -> Tok2 {
+    let tests = fns.iter().flat_map(|def| {
+        let cfgs: Vec<_> = def.meta.iter().filter(|(m, _)| m.name() == "cfg").map(|(m, b)| quote_spanned!(b.span => #[#m])).collect();
+        let signature = def.signature(name);
+        def.examples.iter().map(move |example| (cfgs.clone(), signature.clone(), example))
+    });
+    let tests: Vec<_> = tests
+        .enumerate()
+        .map(|(index, (cfgs, signature, example))| {
+            let test_name = Ident::new(&format!("{}_example_{}", prefix, index), example.span());
+            let message = format!("The example `{}` of `{}` doesn't hold.", tidy_tokens(quote!(#example)), signature);
+            quote!(
+                #[cfg(test)]
+                #(#cfgs)*
+                #[test]
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                fn #test_name() {
+                    assert!(#example, "{}", #message);
+                }
+            )
+        })
+        .collect();
+    quote!(#(#tests)*)
+}
+
+/// Warns about each overload whose argument types only differ from an earlier one's
+/// by one of them being a common `Deref` target of the other's, as with `&String`
+/// and `&str`, since callers may well expect the other overload to be picked.
+fn gen_deref_warnings(fns: &[ParsedFnDef]) -> Tok2 {
+    let types: Vec<Vec<&Type>> = fns.iter().map(|def| def.params.iter().map(|(_, _, ty)| ty).collect()).collect();
+    let tokens = |ty: &Type| quote!(#ty).to_string();
+    let mut warnings = Vec::new();
+    for (later, later_types) in types.iter().enumerate() {
+        for earlier_types in &types[..later] {
+            if earlier_types.len() != later_types.len() {
+                continue;
+            }
+            let differing: Vec<_> = earlier_types
+                .iter()
+                .zip(later_types)
+                .filter(|(earlier, later)| tokens(earlier) != tokens(later))
+                .collect();
+            let (earlier, later) = match differing[..] {
+                [(earlier, later)] if derefs_to(earlier, later) || derefs_to(later, earlier) => (earlier, later),
+                _ => continue,
+            };
+            let note = format!(
+                "`{}` and `{}` are taken by different overloads, although one derefs to the other, \
+                 so each call picks whichever overload matches the argument exactly.",
+                tidy_tokens(quote!(#earlier)),
+                tidy_tokens(quote!(#later)),
+            );
+            warnings.push(quote_spanned! { later.span() =>
+                const _: () = {
+                    #[deprecated(note = #note)]
+                    #[allow(non_camel_case_types)]
+                    struct overlapping_deref;
+                    let _ = overlapping_deref;
+                };
+            });
+        }
+    }
+    quote!(#(#warnings)*)
+}
+
+/// Whether `ty` derefs to `target` through a well-known owning type, as `&String`
+/// does to `&str`, or is a `Vec<T>` commonly passed where `&[T]` is expected.
+fn derefs_to(ty: &Type, target: &Type) -> bool {
+    let (from, to, by_value) = match (ty, target) {
+        (Type::Reference(from), Type::Reference(to)) if from.mutability.is_some() == to.mutability.is_some() => (&*from.elem, &*to.elem, false),
+        (Type::Path(_), Type::Reference(to)) if to.mutability.is_none() => (ty, &*to.elem, true),
+        _ => return false,
+    };
+    let segment = match from {
+        Type::Path(TypePath { qself: None, path }) => match path.segments.last() {
+            Some(segment) => segment.into_value(),
+            None => return false,
+        },
+        _ => return false,
+    };
+    let arg = match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match args.args.first().map(|arg| arg.into_value()) {
+            Some(GenericArgument::Type(ty)) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    };
+    let tokens = |ty: &Type| quote!(#ty).to_string();
+    match (segment.ident.to_string().as_str(), arg, to) {
+        ("Vec", Some(elem), Type::Slice(slice)) => tokens(elem) == tokens(&slice.elem),
+        _ if by_value => false,
+        ("Box", Some(elem), to) => tokens(elem) == tokens(to),
+        (owned, None, Type::Path(TypePath { qself: None, path })) => {
+            let borrowed = match owned {
+                "String" => "str",
+                "PathBuf" => "Path",
+                "OsString" => "OsStr",
+                "CString" => "CStr",
+                _ => return false,
+            };
+            path.segments.last().is_some_and(|segment| segment.value().ident == borrowed)
+        }
+        _ => false,
+    }
+}
+
+/// Takes `#[struct_name = "..."]` out of `attrs`, returning the name to give the struct,
+/// along with a constant of it under the callable name when the two differ.
+fn gen_callable_const(
+    attrs: &mut Vec<Attribute>,
+    vis: &Visibility,
+    name: &Ident,
+    fields: Option<&Punctuated<Field, Token![,]>>,
+) -> Result<(Ident, Tok2)> {
+    let pos = match attrs.iter().position(|attr| attr.path.is_ident("struct_name")) {
+        Some(pos) => pos,
+        None => return Ok((name.clone(), quote!())),
+    };
+    let attr = attrs.remove(pos);
+    let struct_name = match attr.parse_meta() {
+        Ok(Meta::NameValue(MetaNameValue { lit: Lit::Str(lit), .. })) => lit.parse::<Ident>()?,
+        // Camel cases the callable name, as Rust would name the struct.
+        Ok(Meta::Word(_)) => {
+            let camel = camel_case(&name.to_string());
+            if *name == camel {
+                return Err(Error::new_spanned(
+                    attr,
+                    format!("`{}` is already camel cased, so `#[struct_name]` can't give the struct another name. Use `#[struct_name = \"...\"]` instead.", name),
+                ));
+            }
+            Ident::new(&camel, name.span())
+        }
+        _ => return Err(Error::new_spanned(attr, "Expected `#[struct_name]` or `#[struct_name = \"...\"]`.")),
+    };
+    if fields.is_some() {
+        return Err(Error::new_spanned(
+            attr,
+            "`#[struct_name]` is only supported for structs without fields, as the callable name is bound to a constant of it.",
+        ));
+    }
+    let callable_const = quote_spanned! { name.span() =>
+        #[allow(non_upper_case_globals)]
+        #[allow(dead_code)]
+        #vis const #name: #struct_name = #struct_name;
+    };
+    Ok((struct_name, callable_const))
+}
+
+/// Handles `#[instance_const]` among `attrs`, declaring a constant of the struct
+/// named as given, as in `#[instance_const = "MY_FUNC"]`, or after `name` in upper case.
+fn gen_instance_const(
+    attrs: &mut Vec<Attribute>,
+    vis: &Visibility,
+    name: &Ident,
+    struct_name: &Ident,
+    fields: Option<&Punctuated<Field, Token![,]>>,
+) -> Result<Tok2> {
+    let pos = match attrs.iter().position(|attr| attr.path.is_ident("instance_const")) {
+        Some(pos) => pos,
+        None => return Ok(quote!()),
+    };
+    let attr = attrs.remove(pos);
+    let const_name = match attr.parse_meta() {
+        Ok(Meta::Word(_)) => Ident::new(&name.to_string().to_uppercase(), name.span()),
+        Ok(Meta::NameValue(MetaNameValue { lit: Lit::Str(lit), .. })) => lit.parse::<Ident>()?,
+        _ => return Err(Error::new_spanned(attr, "Expected `#[instance_const]` or `#[instance_const = \"...\"]`.")),
+    };
+    if fields.is_some() {
+        return Err(Error::new_spanned(
+            attr,
+            "`#[instance_const]` is only supported for structs without fields, since the constant needs a value of it out of thin air.",
+        ));
+    }
+    Ok(quote_spanned! { const_name.span() =>
+        #[allow(dead_code)]
+        #vis const #const_name: #struct_name = #struct_name;
+    })
+}
+
+/// Declares `SIGNATURES` on `ty`, listing the signature of each overload in `fns`.
+fn gen_signatures(vis: &Visibility, ty: &Ident, name: &Ident, fns: &[ParsedFnDef]) -> Tok2 {
+    let signatures = fns
+        .iter()
+        .cloned()
+        .flat_map(ParsedFnDef::expand_repeat)
+        .flat_map(ParsedFnDef::expand_defaults)
+        .map(|def| def.signature(name));
+    quote!(
+        impl #ty {
+            #[allow(dead_code)]
+            #vis const SIGNATURES: &'static [&'static str] = &[#(#signatures),*];
+        }
+    )
+}
+
+/// The first `#[doc(alias = ...)]` among `attrs`, if any.
+fn doc_alias(attrs: &[Attribute]) -> Option<&Attribute> {
+    attrs.iter().find(|attr| match attr.parse_meta() {
+        Ok(Meta::List(list)) => {
+            list.ident == "doc"
+                && list.nested.iter().any(|nested| match nested {
+                    NestedMeta::Meta(meta) => meta.name() == "alias",
+                    NestedMeta::Literal(_) => false,
+                })
+        }
+        _ => false,
+    })
+}
+
+/// The `#[repr(transparent)]` among `attrs`, if any.
+fn repr_transparent(attrs: &[Attribute]) -> Option<&Attribute> {
+    attrs.iter().find(|attr| match attr.parse_meta() {
+        Ok(Meta::List(list)) => {
+            list.ident == "repr"
+                && list.nested.iter().any(|nested| match nested {
+                    NestedMeta::Meta(meta) => meta.name() == "transparent",
+                    NestedMeta::Literal(_) => false,
+                })
+        }
+        _ => false,
+    })
+}
+
+/// Whether `Default` is already derived for the struct by one of its attributes.
+fn derives_default(attrs: &[Attribute]) -> bool {
+    fn mentions_default(tokens: Tok2) -> bool {
+        tokens.into_iter().any(|tree| match tree {
+            TokenTree::Ident(ref ident) => ident == "Default",
+            TokenTree::Group(ref group) => mentions_default(group.stream()),
+            _ => false,
+        })
+    }
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("derive"))
+        .any(|attr| mentions_default(attr.tts.clone()))
+}
+
+/// Expands `overloadable_stable!`, given the tokens within it.
+pub fn overloadable_stable(input: Tok2) -> Tok2 {
+    match syn::parse2::<OverloadableGlobal>(input) {
+        Ok(parsed) => gen_stable_set(parsed),
+        Err(e) => e.to_compile_error(),
+    }
+}
+
+fn gen_stable_set(parsed: OverloadableGlobal) -> Tok2 {
+    let OverloadableGlobal {
+        attrs,
+        callable,
+        dynamic,
+        documented,
+        vis,
+        name,
+        fields,
+        fns,
+        ..
+    } = parsed;
+    let fields = fields.map(|(_, fields)| fields);
+    let fns: Vec<_> = fns.into_iter().collect();
+    let summary = documented.map(|_| overload_summary(&name, &fns));
+    let mut attrs = attrs;
+    let (struct_name, callable_const) = match gen_callable_const(&mut attrs, &vis, &name, fields.as_ref()) {
+        Ok(renamed) => renamed,
+        Err(e) => return e.to_compile_error(),
+    };
+    let instance_const = gen_instance_const(&mut attrs, &vis, &name, &struct_name, fields.as_ref()).unwrap_or_else(|e| e.to_compile_error());
+    let signatures = if take_attr_word(&mut attrs, "signatures") {
+        gen_signatures(&vis, &struct_name, &name, &fns)
+    } else {
+        quote!()
+    };
+    let deref_warnings = if take_attr_word(&mut attrs, "warn_deref_overlap") {
+        gen_deref_warnings(&fns)
+    } else {
+        quote!()
+    };
+    let examples = gen_examples(&name, &fns, &format!("__{}", name));
+    let priority_macro = gen_priority_macro(&name, &struct_name, &fns);
+    let name = &struct_name;
+    let struct_decl = gen_struct_decl(&attrs, callable.is_some(), summary.as_deref(), &vis, name, fields.as_ref());
+    let trait_name = Ident::new(&format!("{}Overloads", name), name.span());
+    let dyn_name = dynamic.map(|_| dyn_trait_name(name));
+    let fn_decls =
+        gen_fn_decls(fns, name, &vis, Backend::Stable(&trait_name), dyn_name.as_ref(), fields.is_some(), false).unwrap_or_else(|e| e.to_compile_error());
+
+    let expanded = quote! {
+        #struct_decl
+        #callable_const
+        #instance_const
+        #signatures
+        #deref_warnings
+        #examples
+        #priority_macro
+        #[doc(hidden)]
+        #[allow(non_camel_case_types)]
+        #vis trait #trait_name<Args> {
+            type Output;
+            fn call(&self, args: Args) -> Self::Output;
+        }
+        impl #name {
+            // So that `#[track_caller]` on an overload sees through this forwarding.
+            #[track_caller]
+            #[allow(dead_code)]
+            #vis fn call<Args>(&self, args: Args) -> <Self as #trait_name<Args>>::Output
+            where
+                Self: #trait_name<Args>,
+            {
+                <Self as #trait_name<Args>>::call(self, args)
+            }
+        }
+        #(#fn_decls)*
+    };
+    expanded
+}
+
+/// Expands `overloadable_member!`, given the tokens within it.
+pub fn overloadable_member(input: Tok2) -> Tok2 {
+    match syn::parse2::<OverloadableAssociated>(input) {
+        Ok(parsed) => gen_member_set(parsed),
+        Err(e) => e.to_compile_error(),
+    }
+}
+
+fn gen_member_set(parsed: OverloadableAssociated) -> Tok2 {
+    let OverloadableAssociated {
+        grouped,
+        inherent,
+        vis,
+        struct_names,
+        struct_gen,
+        name,
+        items,
+        ..
+    } = parsed;
+    let mut fns = Vec::new();
+    let mut consts = Vec::new();
+    for item in items {
+        match item {
+            MemberItem::Fn(def) => fns.push(def),
+            MemberItem::Const(def) => consts.push(def),
+        }
+    }
+    let consts = gen_member_consts(&consts, &name, &struct_names, &struct_gen, &vis);
+    let inherent = inherent.map(|inherent| inherent.span);
+    let fn_decls = gen_trait_fn_decls(fns, &name, &struct_names, &struct_gen, &vis, grouped.is_some(), inherent)
+        .unwrap_or_else(|e| e.to_compile_error());
+    quote!(
+        #consts
+        #fn_decls
+    )
+}
+
+/// Declares the associated consts of a member overload set on a trait of their own.
+fn gen_member_consts(
+    consts: &[MemberConst],
+    name: &Ident,
+    struct_names: &[Ident],
+    struct_gen: &Generics,
+    vis: &Visibility,
+) -> Tok2 {
+    if consts.is_empty() {
+        return quote!();
+    }
+    let struct_name = &struct_names[0];
+    let trait_name = Ident::new(
+        &format!("{}{}Consts", struct_name, camel_case(&name.to_string())),
+        name.span(),
+    );
+    let types = consts.iter().map(|def| &def.ty);
+    let trait_gen = used_generics(struct_gen, quote!(#(#types)*));
+    let (_, trait_ty_generics, _) = trait_gen.split_for_impl();
+    let (impl_generics, ty_generics, where_clause) = struct_gen.split_for_impl();
+    let decls = consts.iter().map(|MemberConst { attrs, ident, ty, .. }| quote!(#(#attrs)* const #ident: #ty;));
+    let values: Vec<_> = consts
+        .iter()
+        .map(|MemberConst { ident, ty, value, .. }| quote!(const #ident: #ty = #value;))
+        .collect();
+    let values = &values[..];
+    let impls = struct_names.iter().map(|struct_name| quote!(
+        #[automatically_derived]
+        impl #impl_generics #trait_name #trait_ty_generics for #struct_name #ty_generics #where_clause {
+            #(#values)*
+        }
+    ));
+    quote!(
+        #vis trait #trait_name #trait_gen {
+            #(#decls)*
+        }
+        #(#impls)*
+    )
+}
+
+/// A function of an `#[overloads]` item, split into its name and the overload it
+/// declares.
+struct NamedFnDef {
+    vis: Visibility,
+    ident: Ident,
+    def: ParsedFnDef,
+}
+
+impl NamedFnDef {
+    /// Whether `input` starts with a function, as opposed to some other item.
+    fn is_next(input: ParseStream) -> bool {
+        let fork = input.fork();
+        let prefix = |input: ParseStream| -> Result<()> {
+            input.call(Attribute::parse_outer)?;
+            input.parse::<Visibility>()?;
+            input.parse::<Option<Token![const]>>()?;
+            input.parse::<Option<Token![async]>>()?;
+            input.parse::<Option<Token![unsafe]>>()?;
+            input.parse::<Option<Abi>>()?;
+            Ok(())
+        };
+        prefix(&fork).is_ok() && fork.peek(Token![fn])
+    }
+}
+
+impl Parse for NamedFnDef {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        let constness: Option<Token![const]> = input.parse()?;
+        let asyncness: Option<Token![async]> = input.parse()?;
+        let unsafety: Option<Token![unsafe]> = input.parse()?;
+        let abi: Option<Abi> = input.parse()?;
+        let func: Token![fn] = input.parse()?;
+        let ident = input.parse()?;
+        let gen: Generics = input.parse()?;
+        // Left to `ParsedFnDef` as is, since syn can't parse attributes on parameters.
+        let params: TokenTree = input.parse()?;
+        let ret: ReturnType = input.parse()?;
+        let w_clause: Option<WhereClause> = input.parse()?;
+        let code: Block = input.parse()?;
+        let def = syn::parse2(quote!(
+            #(#attrs)* #constness #asyncness #unsafety #abi #func #gen #params #ret #w_clause #code
+        ))?;
+        Ok(Self { vis, ident, def })
+    }
+}
+
+/// The functions of an `#[overloads]` item, and the rest of its items as they were.
+struct OverloadsContent<T> {
+    fns: Vec<NamedFnDef>,
+    others: Vec<T>,
+}
+
+impl<T: Parse> Parse for OverloadsContent<T> {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut fns = Vec::new();
+        let mut others = Vec::new();
+        while !input.is_empty() {
+            if NamedFnDef::is_next(input) {
+                fns.push(input.parse()?);
+            } else {
+                others.push(input.parse()?);
+            }
+        }
+        Ok(Self { fns, others })
+    }
+}
+
+impl<T> OverloadsContent<T> {
+    /// The shared name of the functions, which `span` stands in for if there are none.
+    fn name(&self, span: proc_macro2::Span) -> Result<Ident> {
+        let first = match self.fns.first() {
+            Some(first) => &first.ident,
+            None => return Err(Error::new(span, "`#[overloads]` items must contain at least one function.")),
+        };
+        for NamedFnDef { ident, .. } in &self.fns[1..] {
+            if ident != first {
+                return Err(Error::new(
+                    ident.span(),
+                    format!("Expected `{}`, since the functions of an `#[overloads]` item must share a name.", first),
+                ));
+            }
+        }
+        Ok(first.clone())
+    }
+}
+
+/// The struct named by the self type of an impl, as long as it is given exactly the
+/// impl's generics, so that these can stand in for the struct's own.
+fn impl_struct_name(self_ty: &Type, gen: &Generics) -> Result<Ident> {
+    let error = || {
+        Error::new_spanned(
+            self_ty,
+            "`#[overloads]` impls must be for a struct given exactly the impl's generics, as in `impl<T> Wrapper<T>`.",
+        )
+    };
+    let segment = match self_ty {
+        Type::Path(TypePath { qself: None, path }) if path.segments.len() == 1 => &path.segments[0],
+        _ => return Err(error()),
+    };
+    let args: Vec<_> = match &segment.arguments {
+        PathArguments::None => Vec::new(),
+        PathArguments::AngleBracketed(args) => args.args.iter().collect(),
+        PathArguments::Parenthesized(_) => return Err(error()),
+    };
+    if args.len() != gen.params.len() {
+        return Err(error());
+    }
+    for (arg, param) in args.into_iter().zip(&gen.params) {
+        let matches = match (arg, param) {
+            (GenericArgument::Lifetime(lifetime), GenericParam::Lifetime(def)) => *lifetime == def.lifetime,
+            (GenericArgument::Type(Type::Path(TypePath { qself: None, path })), GenericParam::Type(TypeParam { ident, .. }))
+            | (GenericArgument::Type(Type::Path(TypePath { qself: None, path })), GenericParam::Const(ConstParam { ident, .. })) => {
+                path.is_ident(ident.clone())
+            }
+            _ => false,
+        };
+        if !matches {
+            return Err(error());
+        }
+    }
+    Ok(segment.ident.clone())
+}
+
+/// The item annotated by `#[overloads]`. This is parsed by hand, rather than as a
+/// `syn::Item`, so that parameters can have `#[default(...)]` attributes.
+enum OverloadsTarget {
+    Mod {
+        attrs: Vec<Attribute>,
+        vis: Visibility,
+        mod_token: Token![mod],
+        name: Ident,
+        inner_attrs: Vec<Attribute>,
+        content: OverloadsContent<Item>,
+    },
+    Impl {
+        attrs: Vec<Attribute>,
+        impl_token: Token![impl],
+        gen: Generics,
+        self_ty: Type,
+        inner_attrs: Vec<Attribute>,
+        content: OverloadsContent<ImplItem>,
+    },
+}
+
+impl Parse for OverloadsTarget {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis: Visibility = input.parse()?;
+        let content;
+        if input.peek(Token![mod]) {
+            let mod_token = input.parse()?;
+            let name = input.parse()?;
+            braced!(content in input);
+            Ok(OverloadsTarget::Mod {
+                attrs,
+                vis,
+                mod_token,
+                name,
+                inner_attrs: content.call(Attribute::parse_inner)?,
+                content: content.parse()?,
+            })
+        } else if input.peek(Token![impl]) {
+            if !matches!(vis, Visibility::Inherited) {
+                return Err(Error::new_spanned(vis, "Impls cannot have a visibility."));
+            }
+            let impl_token = input.parse()?;
+            let mut gen: Generics = input.parse()?;
+            let self_ty = input.parse()?;
+            gen.where_clause = input.parse()?;
+            braced!(content in input);
+            Ok(OverloadsTarget::Impl {
+                attrs,
+                impl_token,
+                gen,
+                self_ty,
+                inner_attrs: content.call(Attribute::parse_inner)?,
+                content: content.parse()?,
+            })
+        } else {
+            Err(input.error("`#[overloads]` can only be applied to a module or an inherent impl."))
+        }
+    }
+}
+
+/// Expands `#[overloads]`, given its arguments and the item it is applied to.
+pub fn overloads(args: Tok2, input: Tok2) -> Tok2 {
+    if let Some(arg) = args.into_iter().next() {
+        return Error::new(arg.span(), "`#[overloads]` doesn't take any arguments.").to_compile_error();
+    }
+    let target = match syn::parse2::<OverloadsTarget>(input) {
+        Ok(parsed) => parsed,
+        Err(e) => return e.to_compile_error(),
+    };
+    let expanded = match target {
+        OverloadsTarget::Mod { attrs, vis, mod_token, name, inner_attrs, content } => {
+            gen_overloads_mod(&attrs, &vis, mod_token, &name, &inner_attrs, content)
+        }
+        OverloadsTarget::Impl { attrs, impl_token, gen, self_ty, inner_attrs, content } => {
+            gen_overloads_impl(&attrs, impl_token, &gen, &self_ty, &inner_attrs, content)
+        }
+    };
+    expanded.unwrap_or_else(|e| e.to_compile_error())
+}
+
+fn gen_overloads_mod(
+    attrs: &[Attribute],
+    mod_vis: &Visibility,
+    mod_token: Token![mod],
+    mod_name: &Ident,
+    inner_attrs: &[Attribute],
+    content: OverloadsContent<Item>,
+) -> Result<Tok2> {
+    let name = content.name(mod_name.span())?;
+    let vis = content.fns[0].vis.clone();
+    let vis_string = quote!(#vis).to_string();
+    if let Some(other) = content.fns.iter().find(|NamedFnDef { vis, .. }| quote!(#vis).to_string() != vis_string) {
+        return Err(Error::new(
+            other.ident.span(),
+            "The functions of an `#[overloads]` module must share a visibility, which the overload set is given.",
+        ));
+    }
+    let OverloadsContent { fns, others } = content;
+    let struct_decl = gen_struct_decl(&[], false, None, &vis, &name, None);
+    let fns: Vec<_> = fns.into_iter().map(|def| def.def).collect();
+    let examples = gen_examples(&name, &fns, &format!("__{}", name));
+    let fn_decls = gen_fn_decls(fns, &name, &vis, Backend::FnTraits, None, false, false).unwrap_or_else(|e| e.to_compile_error());
+    Ok(quote!(
+        #(#attrs)*
+        #mod_vis #mod_token #mod_name {
+            #(#inner_attrs)*
+            #(#others)*
+            #struct_decl
+            #fn_decls
+            #examples
+        }
+    ))
+}
+
+fn gen_overloads_impl(
+    attrs: &[Attribute],
+    impl_token: Token![impl],
+    gen: &Generics,
+    self_ty: &Type,
+    inner_attrs: &[Attribute],
+    content: OverloadsContent<ImplItem>,
+) -> Result<Tok2> {
+    let struct_name = impl_struct_name(self_ty, gen)?;
+    let name = content.name(impl_token.span)?;
+    let OverloadsContent { fns, others } = content;
+    let fns = fns.into_iter().map(|NamedFnDef { vis, mut def, .. }| {
+        def.vis = vis;
+        def
+    });
+    let fn_decls = gen_trait_fn_decls(fns, &name, &[struct_name], gen, &Visibility::Inherited, false, None)?;
+    let rest = if others.is_empty() {
+        quote!()
+    } else {
+        let (impl_generics, _, where_clause) = gen.split_for_impl();
+        quote!(
+            #(#attrs)*
+            #impl_token #impl_generics #self_ty #where_clause {
+                #(#inner_attrs)*
+                #(#others)*
+            }
+        )
+    };
+    Ok(quote!(
+        #rest
+        #fn_decls
+    ))
+}
+
+struct CallArgs {
+    func: Expr,
+    args: Punctuated<Expr, Token![,]>,
+}
+
+impl Parse for CallArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let func = input.parse()?;
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+        Ok(Self {
+            func,
+            args: input.parse_terminated(Expr::parse)?,
+        })
+    }
+}
+
+/// Expands `call!`, given the tokens within it.
+pub fn call(input: Tok2) -> Tok2 {
+    let CallArgs { func, args } = match syn::parse2::<CallArgs>(input) {
+        Ok(parsed) => parsed,
+        Err(e) => return e.to_compile_error(),
+    };
+    let args = args.iter();
+    quote!(
+        (#func).call((#(#args,)*))
+    )
+}
+
+struct OverloadableFor {
+    vis: Visibility,
+    name: Ident,
+    /// The type's generic arguments, which must all be concrete.
+    args: Option<AngleBracketedGenericArguments>,
+    _as_keyword: Token![as],
+    fns: Punctuated<ParsedFnDef, Token![,]>,
+}
+
+/// Rejects attributes, such as doc comments, before the name of a type which an
+/// earlier declaration already gave its attributes.
+fn reject_leading_attrs(input: ParseStream, macro_name: &str) -> Result<()> {
+    match input.call(Attribute::parse_outer)?.first() {
+        Some(attr) => Err(Error::new_spanned(
+            attr,
+            format!(
+                "`{}` doesn't declare the type it adds overloads to, so attributes before its name, \
+                 including doc comments, have nothing to apply to. Write them before the overloads instead.",
+                macro_name
+            ),
+        )),
+        None => Ok(()),
+    }
+}
+
+impl Parse for OverloadableFor {
+    fn parse(input: ParseStream) -> Result<Self> {
+        reject_leading_attrs(input, "overloadable_for")?;
+        let vis = input.parse()?;
+        let name = input.parse()?;
+        let args = if input.peek(Token![<]) { Some(input.parse()?) } else { None };
+        let parsed = Self {
+            vis,
+            name,
+            args,
+            _as_keyword: input.parse()?,
+            fns: input.parse_terminated(ParsedFnDef::parse)?,
+        };
+        parsed.check_args_concrete()?;
+        Ok(parsed)
+    }
+}
+
+impl OverloadableFor {
+    /// Errors on generic arguments naming a parameter of one of the overloads, or a
+    /// lifetime other than `'static`, since the impls and methods declared for the
+    /// type can't name them.
+    fn check_args_concrete(&self) -> Result<()> {
+        let args = match &self.args {
+            Some(args) => args,
+            None => return Ok(()),
+        };
+        let params: HashSet<String> = self
+            .fns
+            .iter()
+            .flat_map(|def| &def.gen)
+            .flat_map(|gen| &gen.params)
+            .filter_map(|param| match param {
+                GenericParam::Type(TypeParam { ident, .. }) => Some(ident.to_string()),
+                GenericParam::Const(ConstParam { ident, .. }) => Some(ident.to_string()),
+                GenericParam::Lifetime(_) => None,
+            })
+            .collect();
+        let free = args.args.iter().find(|arg| {
+            let mut idents = HashSet::new();
+            collect_idents(quote!(#arg), &mut idents);
+            match arg {
+                GenericArgument::Lifetime(lifetime) => lifetime.ident != "static",
+                _ => idents.iter().any(|ident| params.contains(ident)),
+            }
+        });
+        match free {
+            Some(arg) => Err(Error::new_spanned(
+                arg,
+                "`overloadable_for` only supports types whose generic arguments are concrete, \
+                 since the impls and methods it declares for the type couldn't name its parameters.",
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Expands `overloadable_for!`, given the tokens within it.
+pub fn overloadable_for(input: Tok2) -> Tok2 {
+    let OverloadableFor { vis, name, args, fns, .. } = match syn::parse2::<OverloadableFor>(input) {
+        Ok(parsed) => parsed,
+        Err(e) => return e.to_compile_error(),
+    };
+    // A type with generic arguments is given a name of its own, which the impls can
+    // then be written for, as for any other.
+    let (alias, name) = match args {
+        Some(args) => {
+            let alias = Ident::new(&format!("{}_{}", name, tokens_suffix(quote!(#args))), name.span());
+            (
+                quote!(
+                    #[doc(hidden)]
+                    #[allow(non_camel_case_types)]
+                    #vis type #alias = #name #args;
+                ),
+                alias,
+            )
+        }
+        None => (quote!(), name),
+    };
+    let fns: Vec<_> = fns.into_iter().collect();
+    let examples = gen_examples(&name, &fns, &format!("__{}", name));
+    let fn_decls = gen_fn_decls(fns, &name, &vis, Backend::FnTraits, None, true, false).unwrap_or_else(|e| e.to_compile_error());
+    quote!(
+        #alias
+        #fn_decls
+        #examples
+    )
+}
+
+struct OverloadableExtend {
+    vis: Visibility,
+    name: Ident,
+    _as_keyword: Token![as],
+    fns: Punctuated<ParsedFnDef, Token![,]>,
+}
+
+impl Parse for OverloadableExtend {
+    fn parse(input: ParseStream) -> Result<Self> {
+        reject_leading_attrs(input, "overloadable_extend")?;
+        Ok(Self {
+            vis: input.parse()?,
+            name: input.parse()?,
+            _as_keyword: input.parse()?,
+            fns: input.parse_terminated(ParsedFnDef::parse)?,
+        })
+    }
+}
+
+/// Expands `overloadable_extend!`, given the tokens within it.
+pub fn overloadable_extend(input: Tok2) -> Tok2 {
+    let OverloadableExtend { vis, name, fns, .. } = match syn::parse2::<OverloadableExtend>(input) {
+        Ok(parsed) => parsed,
+        Err(e) => return e.to_compile_error(),
+    };
+    let fns: Vec<_> = fns.into_iter().collect();
+    // Another invocation may already declare examples for the same name.
+    let signatures: Vec<String> = fns.iter().map(|def| def.signature(&name)).collect();
+    let prefix = format!("__{}_extended_{:08X}", name, fnv1a(&signatures.join(", ")));
+    let examples = gen_examples(&name, &fns, &prefix);
+    let fn_decls = gen_fn_decls(fns, &name, &vis, Backend::FnTraits, None, true, true).unwrap_or_else(|e| e.to_compile_error());
+    quote!(
+        #fn_decls
+        #examples
+    )
+}
+
+/// How an operator's trait method takes its operands.
+#[derive(Clone, Copy, PartialEq)]
+enum OpKind {
+    /// `self` and a right-hand side, returning `Output`, as for `+`.
+    Binary,
+    /// `&mut self` and a right-hand side, as for `+=`.
+    Assign,
+    /// `&self` and a reference to the right-hand side, as for `==`.
+    Compare,
+    /// Only `self`, returning `Output`, as for `!`.
+    Unary,
+}
+
+/// The operators supported by `overloadable_op`, with the module of `core` their trait
+/// is in, the trait, and its method.
+const OPERATORS: &[(&str, &str, &str, &str, OpKind)] = &[
+    ("+", "ops", "Add", "add", OpKind::Binary),
+    ("-", "ops", "Sub", "sub", OpKind::Binary),
+    ("*", "ops", "Mul", "mul", OpKind::Binary),
+    ("/", "ops", "Div", "div", OpKind::Binary),
+    ("%", "ops", "Rem", "rem", OpKind::Binary),
+    ("&", "ops", "BitAnd", "bitand", OpKind::Binary),
+    ("|", "ops", "BitOr", "bitor", OpKind::Binary),
+    ("^", "ops", "BitXor", "bitxor", OpKind::Binary),
+    ("<<", "ops", "Shl", "shl", OpKind::Binary),
+    (">>", "ops", "Shr", "shr", OpKind::Binary),
+    ("+=", "ops", "AddAssign", "add_assign", OpKind::Assign),
+    ("-=", "ops", "SubAssign", "sub_assign", OpKind::Assign),
+    ("*=", "ops", "MulAssign", "mul_assign", OpKind::Assign),
+    ("/=", "ops", "DivAssign", "div_assign", OpKind::Assign),
+    ("%=", "ops", "RemAssign", "rem_assign", OpKind::Assign),
+    ("&=", "ops", "BitAndAssign", "bitand_assign", OpKind::Assign),
+    ("|=", "ops", "BitOrAssign", "bitor_assign", OpKind::Assign),
+    ("^=", "ops", "BitXorAssign", "bitxor_assign", OpKind::Assign),
+    ("<<=", "ops", "ShlAssign", "shl_assign", OpKind::Assign),
+    (">>=", "ops", "ShrAssign", "shr_assign", OpKind::Assign),
+    ("==", "cmp", "PartialEq", "eq", OpKind::Compare),
+    ("<", "cmp", "PartialOrd", "partial_cmp", OpKind::Compare),
+    ("-", "ops", "Neg", "neg", OpKind::Unary),
+    ("!", "ops", "Not", "not", OpKind::Unary),
+];
+
+struct OverloadableOp {
+    op: String,
+    op_span: proc_macro2::Span,
+    _for: Token![for],
+    self_ty: Type,
+    _as_keyword: Token![as],
+    fns: Punctuated<ParsedFnDef, Token![,]>,
+}
+
+impl Parse for OverloadableOp {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let op_span = input.cursor().span();
+        let mut op = String::new();
+        while !input.peek(Token![for]) {
+            match input.parse()? {
+                TokenTree::Punct(punct) => op.push(punct.as_char()),
+                tree => return Err(Error::new(tree.span(), "expected an operator, such as `+` or `==`, followed by `for`")),
+            }
+        }
+        if !OPERATORS.iter().any(|&(symbol, ..)| symbol == op) {
+            return Err(Error::new(op_span, format!("`{}` is not an overloadable operator.", op)));
+        }
+        Ok(Self {
+            op,
+            op_span,
+            _for: input.parse()?,
+            self_ty: input.parse()?,
+            _as_keyword: input.parse()?,
+            fns: input.parse_terminated(ParsedFnDef::parse)?,
+        })
+    }
+}
+
+/// Implements the trait of `op` for `self_ty` with the overload `def`.
+fn gen_op_impl(op: &str, op_span: proc_macro2::Span, self_ty: &Type, def: ParsedFnDef) -> Result<Tok2> {
+    let ParsedFnDef {
+        meta,
+        constness,
+        asyncness,
+        unsafety,
+        abi,
+        trait_name,
+        repeat,
+        gen,
+        paren,
+        this,
+        params,
+        defaults,
+        coercions,
+        examples,
+        ret,
+        w_clause,
+        code,
+        ..
+    } = def;
+    let unsupported = |span: proc_macro2::Span, what: &str| {
+        Err(Error::new(span, format!("Operator overloads cannot be {}, since they implement a trait from `core`.", what)))
+    };
+    if let Some(constness) = constness {
+        return unsupported(constness.span, "`const`");
+    }
+    if let Some(asyncness) = asyncness {
+        return unsupported(asyncness.span, "`async`");
+    }
+    if let Some(unsafety) = unsafety {
+        return unsupported(unsafety.span, "`unsafe`");
+    }
+    if let Some(abi) = abi {
+        return unsupported(abi.extern_token.span, "given an ABI");
+    }
+    if let Some((as_token, _)) = trait_name {
+        return unsupported(as_token.span, "given a trait name");
+    }
+    if let Some(repeat) = repeat {
+        return unsupported(repeat.ident.span(), "repeated");
+    }
+    if let Some(default) = defaults.iter().flatten().next() {
+        return Err(Error::new_spanned(default, "Operator overloads cannot have default arguments."));
+    }
+    if let Some(ty) = coercions.first() {
+        return Err(Error::new_spanned(ty, "Operator overloads cannot be coerced to function pointers."));
+    }
+    if let Some(example) = examples.first() {
+        return Err(Error::new_spanned(example, "Operator overloads cannot have `#[example(...)]`s."));
+    }
+    if let Some((m, _)) = meta.iter().find(|(m, _)| m.name() == "dispatch") {
+        return Err(Error::new_spanned(m, "Operator overloads cannot be `#[dispatch]`ed, since each right-hand side type has a single impl."));
+    }
+    if let Some((m, _)) = meta.iter().find(|(m, _)| m.name() == "overload_priority") {
+        return Err(Error::new_spanned(m, "Operator overloads cannot be given an `#[overload_priority(...)]`, since operators are resolved by the compiler alone."));
+    }
+    let params: Vec<_> = params.into_iter().collect();
+    // `-` is both `Sub` and `Neg`, told apart by whether there is a right-hand side.
+    let unary = params.is_empty();
+    let &(_, module, trait_ident, method, kind) = OPERATORS
+        .iter()
+        .find(|&&(symbol, .., kind)| symbol == op && (kind == OpKind::Unary) == unary)
+        .or_else(|| OPERATORS.iter().find(|&&(symbol, ..)| symbol == op))
+        .expect("operators are checked when parsing");
+    let (receiver, example) = match kind {
+        OpKind::Binary => ("self", "fn(self, rhs: u8) -> Self"),
+        OpKind::Assign => ("&mut self", "fn(&mut self, rhs: u8)"),
+        OpKind::Compare if op == "==" => ("&self", "fn(&self, other: &u8) -> bool"),
+        OpKind::Compare => ("&self", "fn(&self, other: &u8) -> Option<Ordering>"),
+        OpKind::Unary => ("self", "fn(self) -> Self"),
+    };
+    let arity = if kind == OpKind::Unary { 0 } else { 1 };
+    let unspaced = |s: &str| s.replace(' ', "");
+    let this = match this {
+        Some(this) if unspaced(&this.decl_tokens().to_string()).trim_end_matches(',') == unspaced(receiver) && params.len() == arity => this,
+        _ => {
+            return Err(Error::new(paren.span, format!("`{}` overloads take `{}`, as in `{}`.", op, receiver, example)));
+        }
+    };
+    let rhs = match (kind, params.first()) {
+        (OpKind::Compare, Some((_, _, Type::Reference(reference)))) => Some((*reference.elem).clone()),
+        (OpKind::Compare, _) => {
+            return Err(Error::new(paren.span, format!("`{}` overloads take a reference to the right-hand side, as in `{}`.", op, example)));
+        }
+        (_, param) => param.map(|(_, _, ty)| ty.clone()),
+    };
+    check_generics_used(&gen, &w_clause, quote!(#self_ty #rhs), "signature")?;
+    if let Some((m, _)) = meta.iter().find(|(m, _)| m.name() == "deprecated") {
+        return Err(Error::new_spanned(m, "`#[deprecated]` has no effect on operator impls, since using an operator doesn't name the impl."));
+    }
+    let SplitMeta { item: item_meta, method: meta, .. } = split_meta(&meta);
+    let module = Ident::new(module, op_span);
+    let trait_ident = Ident::new(trait_ident, op_span);
+    let method = Ident::new(method, op_span);
+    let trait_path = match &rhs {
+        Some(rhs) => quote!(::core::#module::#trait_ident<#rhs>),
+        None => quote!(::core::#module::#trait_ident),
+    };
+    let params = params.iter().map(|(pat, colon, ty)| quote!(#pat #colon #ty));
+    let (output, ret) = match (kind, ret) {
+        (OpKind::Binary, ReturnType::Default) | (OpKind::Unary, ReturnType::Default) => (quote!(type Output = ();), quote!(-> Self::Output)),
+        (OpKind::Binary, ReturnType::Type(arrow, ty)) | (OpKind::Unary, ReturnType::Type(arrow, ty)) => {
+            (quote_spanned!(ty.span() => type Output = #ty;), quote!(#arrow Self::Output))
+        }
+        (_, ret) => (quote!(), quote!(#ret)),
+    };
+    Ok(quote!(
+        #(#item_meta)*
+        #[automatically_derived]
+        impl #gen #trait_path for #self_ty #w_clause {
+            #output
+            #[inline]
+            #(#meta)*
+            fn #method(#this #(#params),*) #ret #code
+        }
+    ))
+}
+
+/// Expands `overloadable_op!`, given the tokens within it.
+pub fn overloadable_op(input: Tok2) -> Tok2 {
+    let OverloadableOp { op, op_span, self_ty, fns, .. } = match syn::parse2::<OverloadableOp>(input) {
+        Ok(parsed) => parsed,
+        Err(e) => return e.to_compile_error(),
+    };
+    let impls = fns
+        .into_iter()
+        .map(ParsedFnDef::bind_param_attrs)
+        .map(|def| gen_op_impl(&op, op_span, &self_ty, def))
+        .collect::<Result<Vec<_>>>();
+    match impls {
+        Ok(impls) if impls.is_empty() => Error::new(op_span, format!("`{}` must have at least one overload.", op)).to_compile_error(),
+        Ok(impls) => quote!(#(#impls)*),
+        Err(e) => e.to_compile_error(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_global(input: &str) -> String {
+        let OverloadableGlobal { vis, name, fns, .. } = syn::parse_str(input).unwrap();
+        gen_fn_decls(fns, &name, &vis, Backend::FnTraits, None, false, false).unwrap().to_string()
+    }
+
+    fn expand_member(input: &str) -> String {
+        let OverloadableAssociated { inherent, vis, struct_names, struct_gen, name, items, .. } = syn::parse_str(input).unwrap();
+        let fns = items.into_iter().filter_map(|item| match item {
+            MemberItem::Fn(def) => Some(def),
+            MemberItem::Const(_) => None,
+        });
+        let inherent = inherent.map(|inherent| inherent.span);
+        gen_trait_fn_decls(fns, &name, &struct_names, &struct_gen, &vis, false, inherent).unwrap().to_string()
+    }
+
+    #[test]
+    fn unfillable_output_lifetimes() {
+        let check = |input: &str| {
+            let OverloadableAssociated { struct_names, struct_gen, name, items, .. } = syn::parse_str(input).unwrap();
+            let fns = items.into_iter().filter_map(|item| match item {
+                MemberItem::Fn(def) => Some(def),
+                MemberItem::Const(_) => None,
+            });
+            gen_trait_fn_decls(fns, &name, &struct_names, &struct_gen, &Visibility::Inherited, false, None).is_ok()
+        };
+        assert!(check("Foo::f as fn(&self, x: &u8, y: &u8) -> &u8 { x }"));
+        assert!(check("Foo::f as fn(self: Pin<&mut Self>) -> &u8 { &1 }"));
+        assert!(check("Foo::f as fn(x: &u8) -> &u8 { x }"));
+        assert!(!check("Foo::f as fn() -> &str { \"foo\" }"));
+        assert!(!check("Foo::f as fn(self: Box<Self>, x: &u8, y: &u8) -> &u8 { x }"));
+    }
+
+    #[test]
+    fn return_types() {
+        let expanded = expand_global("f as fn(x: u8) -> Vec<u8> { vec![x] }, fn() {}");
+        assert_eq!(expanded.matches("type Output = Vec < u8 > ;").count(), 1);
+        assert_eq!(expanded.matches("type Output = ( ) ;").count(), 1);
+        let expanded = expand_member("Foo::f as fn(&self) -> Vec<u8> { vec![] }, fn() {}");
+        assert_eq!(expanded.matches("-> Vec < u8 >").count(), 2);
+        assert_eq!(expanded.matches("-> ( )").count(), 2);
+        // Along with the inherent function forwarding to the associated overload.
+        let expanded = expand_member("inherent Foo::f as fn(&self) -> Vec<u8> { vec![] }, fn() {}");
+        assert_eq!(expanded.matches("-> ( )").count(), 3);
+    }
+
+    #[test]
+    fn inline_by_default() {
+        let expanded = expand_global("f as fn(x: u8) -> u8 { x }");
+        assert_eq!(expanded.matches("# [ inline ]").count(), 4);
+    }
+
+    #[test]
+    fn inline_always_on_every_method() {
+        let expanded = expand_global("f as #[inline(always)] fn(x: u8) -> u8 { x }");
+        assert_eq!(expanded.matches("# [ inline ( always ) ]").count(), 3);
+        // Only the function pointer shim keeps the default.
+        assert_eq!(expanded.matches("# [ inline ]").count(), 1);
+    }
+
+    #[test]
+    fn cold_on_every_method() {
+        let expanded = expand_global("f as #[cold] #[doc = \"call\"] fn(x: u8) -> u8 { x }");
+        assert_eq!(expanded.matches("# [ cold ]").count(), 3);
+        assert_eq!(expanded.matches("# [ doc = \"call\" ]").count(), 1);
+    }
+
+    #[test]
+    fn documented_struct_is_not_hidden() {
+        let OverloadableGlobal { documented, name, fns, .. } = syn::parse_str(
+            "documented f as #[doc = \" Doubles it.\"] fn<T: Into<u8>>(x: T) -> u8 { x.into() * 2 }, fn(x: &str, (a, b): (u8, u8)) {}",
+        )
+        .unwrap();
+        assert!(documented.is_some());
+        let fns: Vec<_> = fns.into_iter().collect();
+        let summary = overload_summary(&name, &fns);
+        assert_eq!(
+            summary,
+            "\n# Overloads\n\n- `f<T: Into<u8>>(x: T) -> u8`\n\n   Doubles it.\n\n- `f(x: &str, (a, b): (u8, u8))`\n",
+        );
+        let decl = gen_struct_decl(&[], false, Some(&summary), &Visibility::Inherited, &name, None).to_string();
+        assert!(!decl.contains("hidden"));
+        assert!(decl.contains("# Overloads"));
+        let decl = gen_struct_decl(&[], false, None, &Visibility::Inherited, &name, None).to_string();
+        assert!(decl.contains("# [ doc ( hidden ) ]"));
+    }
+
+    #[test]
+    fn camel_cased_struct_names() {
+        let mut attrs = vec![parse_quote!(#[struct_name])];
+        let name: Ident = parse_quote!(my_func);
+        let (struct_name, callable_const) = gen_callable_const(&mut attrs, &Visibility::Inherited, &name, None).unwrap();
+        assert_eq!(struct_name, "MyFunc");
+        assert!(callable_const.to_string().contains("const my_func : MyFunc = MyFunc ;"));
+        let decl = gen_struct_decl(&attrs, false, None, &Visibility::Inherited, &struct_name, None).to_string();
+        assert!(!decl.contains("non_camel_case_types"));
+        let decl = gen_struct_decl(&attrs, false, None, &Visibility::Inherited, &name, None).to_string();
+        assert!(decl.contains("non_camel_case_types"));
+        let mut attrs = vec![parse_quote!(#[struct_name])];
+        assert!(gen_callable_const(&mut attrs, &Visibility::Inherited, &parse_quote!(MyFunc), None).is_err());
+    }
+
+    #[test]
+    fn impl_trait_lifetimes() {
+        let expanded = expand_global("f as fn<T>(x: impl Iterator<Item = &'a T>, y: impl Iterator<Item = &u8>) {}");
+        let header = "impl < '__overloadable_impl1 , 'a , T , __OverloadableImpl0 : Iterator < Item = & 'a T > , \
+                      __OverloadableImpl1 : Iterator < Item = & '__overloadable_impl1 u8 > > :: core :: ops :: Fn";
+        assert!(expanded.contains(header));
+        // Those of `Fn(...)` bounds are left to elide on their own.
+        let expanded = expand_global("f as fn(x: impl Fn(&u8) -> &u8) {}");
+        assert!(!expanded.contains("'__overloadable_impl0"));
+    }
+
+    #[test]
+    fn where_clauses_are_kept_whole() {
+        let expanded = expand_global("f as fn<A, B, C>(a: A, b: B, c: C) -> <B::Output as Add<C>>::Output where B: Add<A>, B::Output: Add<C> { b + a + c }");
+        let header = "impl < A , B , C > :: core :: ops :: Fn < ( A , B , C , ) > for f where B : Add < A > , B :: Output : Add < C > {";
+        assert!(expanded.contains(header));
+    }
+
+    #[test]
+    fn elided_output_lifetimes() {
+        let expanded = expand_global("f as fn(x: &[u8]) -> &u8 { &x[0] }, fn<'a>(x: &'a str, y: u8) -> &str { x }");
+        assert!(expanded.contains("type Output = & '__overloadable_elided u8 ;"));
+        assert!(expanded.contains("type Output = & 'a str ;"));
+        // Which argument it borrows from is ambiguous, as it would be for a function.
+        let expanded = expand_global("f as fn(x: &str, y: &str) -> &str { x }");
+        assert!(!expanded.contains("'__overloadable_elided"));
+    }
+
+    #[test]
+    fn leading_doc_comments() {
+        let OverloadableGlobal { attrs, name, .. } = syn::parse_str("/// Doubles it.\n#[derive(Clone)] pub f as fn(x: u8) -> u8 { x * 2 }").unwrap();
+        assert_eq!(attrs.len(), 2);
+        // They are kept, if hidden along with the rest of the struct.
+        let decl = gen_struct_decl(&attrs, false, None, &Visibility::Inherited, &name, None).to_string();
+        assert!(decl.contains("\" Doubles it.\""));
+        assert!(decl.contains("# [ doc ( hidden ) ]"));
+        assert!(syn::parse_str::<OverloadableFor>("/// Doubles it.\nF as fn(x: u8) -> u8 { x * 2 }").is_err());
+    }
+
+    #[test]
+    fn sized_supertraits() {
+        let expanded = expand_member(
+            "Foo::f as fn(self) {}, fn(self: Box<Self>, x: u8) {}, fn(&self) {}, fn(self: Pin<&mut Self>, x: u16) {}",
+        );
+        assert_eq!(expanded.matches(": :: core :: marker :: Sized {").count(), 1);
+        let expanded = expand_member("Foo::f as #[maybe_unsized] fn(self) {}, #[sized] fn(self: Box<Self>, x: u8) {}, #[sized] fn(&self) {}");
+        assert_eq!(expanded.matches(": :: core :: marker :: Sized {").count(), 2);
+        assert!(!expanded.contains("sized ]"));
+        // Returning `Self` by value only needs it to be sized where the trait is implemented.
+        let expanded = expand_member("Foo::f as fn() -> Self { Foo }, fn(&self) -> Self { Foo }");
+        assert!(!expanded.contains("Sized"));
+    }
+
+    #[test]
+    fn impls_are_automatically_derived() {
+        let count = |expanded: &str, token: &str| expanded.split_whitespace().filter(|t| *t == token).count();
+        let expanded = expand_global("f as fn(x: u8) -> u8 { x }, fn<T>(x: T, y: T) {}");
+        assert_eq!(count(&expanded, "impl"), 14);
+        assert_eq!(count(&expanded, "automatically_derived"), 14);
+        let expanded = expand_member("Foo::f as fn(&self) {}, fn(self, x: u8) {}");
+        assert_eq!(count(&expanded, "impl"), 2);
+        assert_eq!(count(&expanded, "automatically_derived"), 2);
+    }
+
+    #[test]
+    fn doc_aliases() {
+        let OverloadableGlobal { attrs, name, .. } =
+            syn::parse_str("#[doc(alias = \"surface\")] documented area as fn(x: f32) -> f32 { x * x }").unwrap();
+        let decl = gen_struct_decl(&attrs, false, Some(""), &Visibility::Inherited, &name, None).to_string();
+        assert!(decl.contains("# [ doc ( alias = \"surface\" ) ]"));
+        assert!(!decl.contains("compile_error"));
+        let decl = gen_struct_decl(&attrs, false, None, &Visibility::Inherited, &name, None).to_string();
+        assert!(decl.contains("compile_error"));
+    }
+
+    #[test]
+    fn conditional_hints() {
+        let expanded = expand_global(
+            "f as #[cfg_attr(feature = \"fast\", inline(always), must_use)] fn(x: u8) -> u8 { x }",
+        );
+        assert_eq!(expanded.matches("cfg_attr ( feature = \"fast\" , inline ( always ) ) ]").count(), 3);
+        assert_eq!(expanded.matches("cfg_attr ( not ( any ( feature = \"fast\" ) ) , inline ) ]").count(), 3);
+        assert_eq!(expanded.matches("cfg_attr ( feature = \"fast\" , must_use ) ]").count(), 1);
+    }
+
+    #[test]
+    fn examples() {
+        let OverloadableGlobal { name, fns, .. } = syn::parse_str(
+            "f as #[example(f(1) == 2)] #[cfg(unix)] fn(x: u8) -> u8 { x + 1 }, #[example(f(\"\") == 1)] fn(x: &str) -> usize { x.len() }",
+        )
+        .unwrap();
+        let fns: Vec<_> = fns.into_iter().collect();
+        let expanded = gen_examples(&name, &fns, "__f").to_string();
+        assert!(!expanded.contains("mod "));
+        assert!(expanded.contains("# [ cfg ( test ) ] # [ cfg ( unix ) ] # [ test ]"));
+        assert!(expanded.contains("fn __f_example_0 ( )"));
+        assert!(expanded.contains("assert ! ( f ( 1 ) == 2"));
+        // Asserted like any other, so a wrong example fails its test.
+        assert!(expanded.contains("The example `f(\\\"\\\") == 1` of `f(x: &str) -> usize` doesn't hold."));
+        let fns: Vec<_> = fns.into_iter().map(|def| ParsedFnDef { examples: Vec::new(), ..def }).collect();
+        assert!(gen_examples(&name, &fns, "__f").is_empty());
+    }
+
+    #[test]
+    fn boxed_bodies() {
+        let expanded = expand_global("f as #[boxed] fn(x: u8) -> impl Fn(u8) -> u8 { move |y| x + y }");
+        assert!(expanded.contains("let __overloadable_value = { move | y | x + y } ;"));
+        assert!(expanded.contains("Box :: new ( __overloadable_value )"));
+    }
+
+    #[test]
+    fn deref_targets() {
+        let ty = |ty: &str| syn::parse_str::<Type>(ty).unwrap();
+        assert!(derefs_to(&ty("&String"), &ty("&str")));
+        assert!(derefs_to(&ty("&mut std::path::PathBuf"), &ty("&mut Path")));
+        assert!(derefs_to(&ty("&Box<u8>"), &ty("&u8")));
+        assert!(derefs_to(&ty("Vec<u8>"), &ty("&[u8]")));
+        assert!(!derefs_to(&ty("&str"), &ty("&String")));
+        assert!(!derefs_to(&ty("&String"), &ty("&mut str")));
+        assert!(!derefs_to(&ty("Vec<u8>"), &ty("&[u16]")));
+        assert!(!derefs_to(&ty("Box<u8>"), &ty("&u8")));
+    }
+
+    #[test]
+    fn param_attrs_on_bindings() {
+        let expanded = expand_global("f as fn(x: u8, #[allow(unused_variables)] y: u8) -> u8 { x }");
+        assert!(expanded.contains("( x , __param_1 , ) : ( u8 , u8 , )"));
+        assert!(expanded.contains("# [ allow ( unused_variables ) ] let y : u8 = __param_1 ;"));
+        let expanded = expand_member("Foo::f as fn(&self, #[allow(unused_variables)] y: u8) {}");
+        assert!(expanded.contains("# [ allow ( unused_variables ) ] y : u8"));
+    }
+
+    #[test]
+    fn names_required_features() {
+        let expanded = expand_global("f as fn(x: u8) -> u8 { x }");
+        assert!(expanded.contains("#![feature(unboxed_closures, fn_traits)]"));
+    }
+
+    #[test]
+    fn higher_ranked_where_clause_on_every_impl() {
+        let expanded = expand_global("f as fn<F>(f: F) where F: for<'a> Fn(&'a str) -> &'a str {}");
+        // Once for each of the `Fn*` traits, on both the struct and the `with` wrapper.
+        assert_eq!(expanded.matches("where F : for < 'a > Fn ( & 'a str ) -> & 'a str").count(), 6);
+    }
+}
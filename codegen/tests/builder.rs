@@ -0,0 +1,72 @@
+use overloadable_codegen::{overloadable, overloadable_member, overloadable_stable, Overload, OverloadSet};
+use quote::quote;
+use syn::{parse_quote, Generics};
+
+fn doubling() -> Vec<Overload> {
+    let mut generics: Generics = parse_quote!(<T: Clone>);
+    generics.where_clause = Some(parse_quote!(where T: Default));
+    vec![
+        Overload::new(vec![(parse_quote!(x), parse_quote!(u8))], parse_quote!(-> u8), parse_quote!({ x * 2 })),
+        Overload {
+            generics,
+            ..Overload::new(vec![(parse_quote!(x), parse_quote!(T))], parse_quote!(-> (T, T)), parse_quote!({ (x.clone(), x) }))
+        },
+    ]
+}
+
+#[test]
+fn global_sets() {
+    let set = doubling()
+        .into_iter()
+        .fold(OverloadSet::new(parse_quote!(double)).vis(parse_quote!(pub)).attr(parse_quote!(#[derive(Debug)])), OverloadSet::overload);
+    let expected = overloadable(quote!(
+        #[derive(Debug)]
+        pub double as
+        fn(x: u8) -> u8 { x * 2 },
+        fn<T: Clone>(x: T) -> (T, T) where T: Default { (x.clone(), x) }
+    ));
+    let expanded = quote!(#set).to_string();
+    assert_eq!(expanded, expected.to_string());
+    assert!(expanded.contains(":: core :: ops :: Fn < ( u8 , ) > for double"));
+}
+
+#[test]
+fn stable_sets() {
+    let set = doubling().into_iter().fold(OverloadSet::stable(parse_quote!(double)), OverloadSet::overload);
+    let expected = overloadable_stable(quote!(
+        double as
+        fn(x: u8) -> u8 { x * 2 },
+        fn<T: Clone>(x: T) -> (T, T) where T: Default { (x.clone(), x) }
+    ));
+    assert_eq!(quote!(#set).to_string(), expected.to_string());
+}
+
+#[test]
+fn member_sets() {
+    let set = OverloadSet::member(parse_quote!(Counter), parse_quote!(get))
+        .vis(parse_quote!(pub))
+        .overload(Overload { receiver: Some(parse_quote!(&self)), ..Overload::new(vec![], parse_quote!(-> u32), parse_quote!({ self.0 })) })
+        .overload(Overload { receiver: Some(parse_quote!(self)), ..Overload::new(vec![], parse_quote!(-> u32), parse_quote!({ self.0 + 1 })) });
+    let expected = overloadable_member(quote!(
+        pub Counter::get as
+        fn(&self) -> u32 { self.0 },
+        fn(self) -> u32 { self.0 + 1 }
+    ));
+    assert_eq!(quote!(#set).to_string(), expected.to_string());
+}
+
+#[test]
+fn errors_become_compile_errors() {
+    let set = OverloadSet::new(parse_quote!(double));
+    assert!(quote!(#set).to_string().contains("compile_error"));
+}
+
+#[test]
+fn invalid_overloads_are_errors() {
+    let receiving = Overload { receiver: Some(parse_quote!(&self)), ..Overload::new(vec![], parse_quote!(-> u32), parse_quote!({ 1 })) };
+    let error = OverloadSet::new(parse_quote!(double)).overload(receiving.clone()).expand().err().unwrap();
+    assert!(error.to_string().contains("`self`-style parameter"));
+    assert!(OverloadSet::member(parse_quote!(Counter), parse_quote!(get)).overload(receiving).expand().is_ok());
+    let refutable = Overload::new(vec![(parse_quote!(1), parse_quote!(u8))], parse_quote!(-> u8), parse_quote!({ 1 }));
+    assert!(OverloadSet::new(parse_quote!(double)).overload(refutable).expand().is_err());
+}
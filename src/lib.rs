@@ -28,7 +28,7 @@
       fn(x: usize, y: &str) -> f32 {
           (x * y.len()) as f32
       },
-      fn<T>() where T: Debug {}
+      fn<T>(x: T) where T: Debug {}
   }
   //Gives
   #[allow(non_camel_case_types)]
@@ -41,8 +41,8 @@
       }
   }
   //The rest of the `Fn*` family
-  impl<T> Fn<()> for my_func where T: Debug {
-      extern "rust-call" fn call(&self, (): ()) -> () {
+  impl<T> Fn<(T,)> for my_func where T: Debug {
+      extern "rust-call" fn call(&self, (x,): (T,)) -> () {
           {}
       }
   }
@@ -50,333 +50,225 @@
   ```
 
   Note that you cannot have functions with unused generic parameters due to the
-  trait-implementing nature of this method.
-*/
-extern crate proc_macro;
-use self::proc_macro::TokenStream;
-use proc_macro2::TokenStream as Tok2;
-use quote::{quote, quote_spanned, ToTokens};
-use syn::{
-    bracketed,
-    parenthesized,
-    parse::{Parse, ParseStream, Result},
-    parse_macro_input,
-    punctuated::Punctuated,
-    spanned::Spanned,
-    token::{Bracket, Paren},
-    Block,
-    Error,
-    Generics,
-    Ident,
-    Meta,
-    Pat,
-    ReturnType,
-    Token,
-    Type,
-    TypeTuple,
-    Visibility,
-    WhereClause,
-};
-
-struct OverloadableGlobal {
-    vis: Visibility,
-    name: Ident,
-    _as_keyword: Token![as],
-    fns: Punctuated<ParsedFnDef, Token![,]>,
-}
+  trait-implementing nature of this method, and such overloads are rejected with an
+  error pointing at the offending parameter.
+  Alternatively, annotating an overload with `#[phantom]` appends a trailing
+  `PhantomData` argument mentioning all of its generic parameters, so that callers
+  can select them with, for example, `my_func(PhantomData::<(u8,)>)`.
 
-impl Parse for OverloadableGlobal {
-    fn parse(input: ParseStream) -> Result<Self> {
-        Ok(Self {
-            vis: input.parse()?,
-            name: input.parse()?,
-            _as_keyword: input.parse()?,
-            fns: input.parse_terminated(ParsedFnDef::parse)?,
-        })
-    }
-}
+  `Self` refers to the generated struct, so an overload can be declared as
+  `fn() -> Self { Self }`.
 
-struct OverloadableAssociated {
-    vis: Visibility,
-    struct_name: Ident,
-    _colons: Token![::],
-    name: Ident,
-    _as: Token![as],
-    fns: Punctuated<ParsedFnDef, Token![,]>,
-}
+  A body consisting of a single expression can be written after `=>` instead of in
+  braces, as in `fn(x: u8) -> u8 => x + 1`. This works in every macro of this crate.
 
-impl Parse for OverloadableAssociated {
-    fn parse(input: ParseStream) -> Result<Self> {
-        Ok(Self {
-            vis: input.parse()?,
-            struct_name: input.parse()?,
-            _colons: input.parse()?,
-            name: input.parse()?,
-            _as: input.parse()?,
-            fns: input.parse_terminated(ParsedFnDef::parse)?,
-        })
-    }
-}
+  Parameters are destructured from the argument tuple, which is taken by value, so
+  `ref` and `ref mut` bindings, as in `fn((ref a, ref b): (String, String))`, borrow
+  from it for the rest of the body rather than moving out of it.
+  Parameters may also be given lint attributes, such as `#[allow(unused_variables)]`,
+  which then apply to their bindings.
 
-enum ThisDef {
-    Explicit(
-        Option<Token![mut]>,
-        Token![self],
-        Token![:],
-        Type,
-        Option<Token![,]>,
-    ),
-    Implicit(
-        Option<Token![&]>,
-        Option<Token![mut]>,
-        Token![self],
-        Option<Token![,]>,
-    ),
-}
+  Overloads marked `#[dispatch]` may instead take refutable patterns, as in
+  `#[dispatch] fn(Some(x): Option<u8>) -> u8 => x` alongside
+  `#[dispatch] fn(None: Option<u8>) -> u8 => 0`. Those taking the same argument types
+  are merged into a single impl, which matches the arguments against their patterns
+  in order, so they must share their generics and return type, and together cover
+  every value. Only the first of them may have other attributes.
 
-impl Parse for ThisDef {
-    fn parse(input: ParseStream) -> Result<Self> {
-        if (input.peek2(Token![:]) || input.peek3(Token![:]))
-            && (input.peek(Token![self]) || input.peek2(Token![self]))
-        {
-            //Explicit route
-            let mutable = if input.peek(Token![mut]) {
-                Some(input.parse::<Token![mut]>()?)
-            } else {
-                None
-            };
-            let this = input.parse::<Token![self]>()?;
-            Ok(ThisDef::Explicit(
-                mutable,
-                this,
-                input.parse()?,
-                input.parse()?,
-                input.parse()?,
-            ))
-        } else if input.peek(Token![self]) || input.peek2(Token![self]) || input.peek3(Token![self])
-        {
-            Ok(ThisDef::Implicit(
-                input.parse()?,
-                input.parse()?,
-                input.parse()?,
-                input.parse()?,
-            ))
-        } else {
-            Err(input.error("Could not find self type!"))
-        }
-    }
-}
+  Trailing parameters may be given a default value, as in
+  `fn(x: u8, #[default(0)] y: u8) -> u8 { x + y }`, in which case an overload is also
+  declared for each number of them which can be omitted, such as `fn(x: u8)` here.
 
-impl ToTokens for ThisDef {
-    fn to_tokens(&self, tokens: &mut Tok2) {
-        match self {
-            ThisDef::Explicit(mut_def, self_def, colon, ty, comma) => {
-                mut_def.to_tokens(tokens);
-                self_def.to_tokens(tokens);
-                colon.to_tokens(tokens);
-                ty.to_tokens(tokens);
-                comma.to_tokens(tokens);
-            }
-            ThisDef::Implicit(and, mut_def, self_def, comma) => {
-                and.to_tokens(tokens);
-                mut_def.to_tokens(tokens);
-                self_def.to_tokens(tokens);
-                comma.to_tokens(tokens);
-            }
-        }
-    }
-}
+  Arguments aren't coerced when calling an overload, so a `u8` can't be passed to
+  `fn(x: u64)`. Annotating it with `#[coerce_via(Into)]` instead takes any arguments
+  which convert into the declared types, as in `impl<X: Into<u64>> Fn<(X,)>`, and
+  converts them at the start of the body. Since any type might implement `Into<u64>`,
+  no other overload may take as many arguments.
 
-impl ThisDef {
-    pub fn is_sized_dependent(this: &Option<Self>) -> bool {
-        if let Some(ThisDef::Implicit(None, ..)) = this { true }
-        else { false }
-    }
-}
+  Where a call could go to more than one overload, as an unsuffixed literal could be
+  given to both `fn(x: u32)` and `fn(x: u64)`, the compiler refuses to pick one.
+  Annotating overloads with `#[overload_priority(n)]`, for `n` from 0 to 15, also
+  declares a macro named after the set, which calls the overload of the highest
+  priority that the arguments can be given to, so that `my_func!(1)` calls
+  `#[overload_priority(1)] fn(x: u64)` over `fn(x: u32)`, whose priority is 0. It
+  wraps the arguments in one more reference than the highest priority, which method
+  resolution then peels off one at a time, trying the overloads of each priority in
+  turn. The macro is declared where the set is, and can only be used after it in the
+  same module. Calling the set itself is unaffected, and overload sets with fields
+  can't be prioritized, since the macro has to name the struct's value.
 
-struct ParsedFnDef {
-    meta: Vec<(Meta, Bracket)>,
-    _func: Token![fn],
-    gen: Option<Generics>,
-    paren: Paren,
-    this: Option<ThisDef>,
-    params: Punctuated<(Pat, Token![:], Type), Token![,]>,
-    ret: ReturnType,
-    w_clause: Option<WhereClause>,
-    code: Block,
-}
+  Each overload may also carry examples, as in `#[example(my_func(1) == 2)]`, which
+  are asserted by a `#[test]` generated alongside the overloads, under
+  `#[cfg(test)]`, so that a wrong example fails `cargo test` without having to be
+  written out again. The tests are named `__my_func_example_0` and so on, and are
+  declared where the set is, so the harness only runs those of a set declared at
+  module level, rather than in a function body.
 
-fn parse_pattern_type_pair(input: ParseStream) -> Result<(Pat, Token![:], Type)> {
-    Ok((input.parse()?, input.parse()?, input.parse()?))
-}
+  An overload whose generics start with `repeat N in 1..=4` is declared once for each
+  value of `N` in the range, which is a `usize` constant in its body. Each parameter
+  of type `[T; N]` is then taken as `N` separate arguments, so that
+  `fn<repeat N in 1..=4>(xs: [u32; N]) -> u32 { xs.iter().sum() }` can be called with
+  anywhere from one to four `u32`s, and binds them to the array `xs`.
 
-impl Parse for ParsedFnDef {
-    fn parse(input: ParseStream) -> Result<Self> {
-        let mut meta = Vec::new();
-        while input.peek(Token![#]) {
-            input.parse::<Token![#]>()?;
-            let meta_content;
-            let brackets = bracketed!(meta_content in input);
-            meta.push((meta_content.parse()?, brackets))
-        }
-        let _func = input.parse::<Token![fn]>()?;
-        let gen = if input.peek(Token![<]) {
-            Some(input.parse::<Generics>()?)
-        } else {
-            None
-        };
-        let params_content;
-        let paren = parenthesized!(params_content in input);
-        let this = params_content.parse::<ThisDef>().ok();
-        let params = params_content.parse_terminated(parse_pattern_type_pair)?;
-        let ret = input.parse()?;
-        let w_clause = if input.peek(Token![where]) {
-            Some(input.parse::<WhereClause>()?)
-        } else {
-            None
-        };
-        let code = input.parse()?;
-        Ok(Self {
-            meta,
-            _func,
-            gen,
-            paren,
-            this,
-            params,
-            ret,
-            w_clause,
-            code,
-        })
-    }
-}
+  Const generic parameters are supported like any other, so one overload can take
+  `fn<const N: usize>(x: [u16; N])` while others take `[u8; 4]` and `[u8; 8]`. With
+  `#![feature(generic_const_exprs)]`, they can also be bounded by const expressions,
+  as in `fn<const N: usize>(x: [u8; N]) -> [u8; N - 1] where [(); N - 1]:`.
 
-fn gen_fn_decls<T: IntoIterator<Item = ParsedFnDef>>(fns: T, name: &Ident) -> Result<Tok2> {
-    let fns: Vec<Tok2> = fns.into_iter().map(
-        |ParsedFnDef {
-             gen,
-             params,
-             ret,
-             w_clause,
-             code,
-             paren,
-             meta,
-             this,
-             ..
-        }| {
-            if this.is_some() {
-                return Err(Error::new(paren.span, "This declaration cannot contain a `self`-style parameter."));
-            }
-            let ret = match ret {
-                ReturnType::Type(_, ty) => *ty.clone(),
-                ReturnType::Default => Type::Tuple(TypeTuple { paren_token: paren, elems: Punctuated::new() }),
-            };
-            let mut param_types = Vec::new();
-            let mut param_patterns = Vec::new();
-            params.iter().for_each(|(pat, _, ty)| {
-                param_types.push(ty);
-                param_patterns.push(pat)
-            });
-            let pty = &param_types[..];
-            let ppt = &param_patterns[..];
-            let meta: Vec<Tok2> = meta.iter().map(|(m, b)| quote_spanned!(b.span => #[#m])).collect();
-            let meta = &meta[..];
-            Ok(quote!(
-                impl#gen Fn<(#(#pty,)*)> for #name #w_clause {
-                    #(#meta)*
-                    extern "rust-call" fn call(&self, (#(#ppt,)*): (#(#pty,)*)) -> Self::Output {
-                        #code
-                    }
-                }
-                impl#gen FnOnce<(#(#pty,)*)> for #name #w_clause {
-                    type Output = #ret;
-                    #(#meta)*
-                    extern "rust-call" fn call_once(self, x: (#(#pty,)*)) -> Self::Output {
-                        self.call(x)
-                    }
-                }
-                impl#gen FnMut<(#(#pty,)*)> for #name #w_clause {
-                    #(#meta)*
-                    extern "rust-call" fn call_mut(&mut self, x: (#(#pty,)*)) -> Self::Output {
-                        self.call(x)
-                    }
-                }
-            ))
-        }
-    ).collect::<Result<Vec<Tok2>>>()?;
-    Ok(quote!(
-        #(#fns)*
-    ))
-}
+  Every generic type or const parameter has to be inferable from the arguments, so it
+  must appear in their types, or be given to an associated type in a bound, as `U`
+  in `where T: Iterator<Item = U>`. Lifetimes which are only named in the where clause,
+  as in `fn(a: &'a str, b: &'b str) -> &'b str where 'a: 'b`, are declared implicitly.
+  Lifetimes elided in the return type are filled in as they would be for a function,
+  so `fn(x: &[u8]) -> &[u8]` borrows from `x`, as long as only one argument has a
+  lifetime.
+  Arguments may also be of an `impl Trait` type, as in
+  `fn(x: impl Iterator<Item = &'a u8>)`, which becomes a type parameter of the impls,
+  along with lifetime parameters for those its bounds name, and one for any they elide.
+  Return types may project out of the bounds, as in
+  `fn<I: IntoIterator>(x: I) -> I::IntoIter`, since those are carried over to the impl.
+  The where clause is carried over whole, so its predicates may refer to one another's
+  parameters and associated types, as in `where B: Add<A>, B::Output: Add<C>`.
 
-fn gen_trait_fn_decls<T: IntoIterator<Item = ParsedFnDef>>(
-    fns: T,
-    name: &Ident,
-    struct_name: &Ident,
-    vis: &Visibility,
-) -> Result<Tok2> {
-    let fns: Vec<Tok2> = fns
-        .into_iter()
-        .enumerate()
-        .map(
-            |(
-                index,
-                ParsedFnDef {
-                    gen,
-                    params,
-                    ret,
-                    w_clause,
-                    code,
-                    paren,
-                    meta,
-                    this,
-                    ..
-                },
-            )| {
-                let ret = match ret {
-                    ReturnType::Type(_, ty) => *ty.clone(),
-                    ReturnType::Default => Type::Tuple(TypeTuple {
-                        paren_token: paren,
-                        elems: Punctuated::new(),
-                    }),
-                };
-                let mut trait_params = Vec::with_capacity(params.len());
-                let mut impl_params = Vec::with_capacity(params.len());
-                for (index, (lhs, _, rhs)) in params.iter().enumerate() {
-                    let next_ident = Ident::new(&format!("_{}", index), lhs.span());
-                    trait_params.push(quote!(#next_ident: #rhs));
-                    impl_params.push(quote!(#lhs: #rhs));
-                }
-                let meta: Vec<Tok2> = meta
-                    .iter()
-                    .map(|(m, b)| quote_spanned!(b.span => #[#m]))
-                    .collect();
-                let meta = &meta[..];
-                let trait_name = Ident::new(
-                    &format!("{}Trait{}", struct_name, index),
-                    struct_name.span(),
-                );
-                let sized_requirement = if ThisDef::is_sized_dependent(&this) {
-                    quote!(Sized)
-                } else { quote!() };
-                Ok(quote!(
-                    #vis trait #trait_name: #sized_requirement {
-                        fn #name#gen(#this#(#trait_params),*) -> #ret #w_clause;
-                    }
-                    impl #trait_name for #struct_name {
-                        #(#meta)*
-                        fn #name#gen(#this#(#impl_params),*) -> #ret #w_clause {
-                            #code
-                        }
-                    }
-                ))
-            },
-        )
-        .collect::<Result<Vec<Tok2>>>()?;
-
-    Ok(quote!(
-        #(#fns)*
-    ))
-}
+  Attributes written before the name, such as `#[derive(Clone, Copy)]`, are applied
+  to the generated struct. The struct also implements `Default`, unless it is already
+  derived, and has a `const fn new()`, so that `my_func::new()` gives an instance which
+  can be moved around like any other value.
+
+  The struct can also be given fields, as in `my_func { factor: u32 } as ...`, which the
+  overloads can then use through `self`, such as `self.factor`. It is then constructed
+  like any other struct, or through `my_func::new(3)`, and gets no `Default` impl or
+  function pointer accessors, since those need a value of it out of thin air.
+  Its layout can be controlled with `#[repr(C)]` or, for a struct with exactly one
+  field, `#[repr(transparent)]`.
+  Overloads can likewise be implemented for a type declared elsewhere in the crate with
+  `overloadable_for!(MyType as ...)`.
+  More overloads can be added to a struct declared by an earlier invocation with
+  `overloadable_extend!(my_func as ...)`, such as from another module. Neither accepts
+  attributes or doc comments before the name, since the type is declared elsewhere.
+
+  An attribute `#[struct_name = "MyFuncFn"]` before the name gives the struct that name
+  instead, and binds the callable name to a constant of it, as in
+  `const my_func: MyFuncFn = MyFuncFn;`. This leaves the name free in the type
+  namespace, such as for a module `my_func` declaring a function of the same name. It
+  is only supported for structs without fields. Written without a name, as
+  `#[struct_name]`, it camel cases the callable name, giving `struct MyFunc;` and
+  `const my_func: MyFunc = MyFunc;`, so that the struct needn't allow
+  `non_camel_case_types`.
+
+  Likewise, `#[instance_const]` before the name declares a constant of the struct,
+  named after the overload set in upper case, as in
+  `pub const MY_FUNC: my_func = my_func;`, or given its name by
+  `#[instance_const = "MY_FUNC"]`. It can be used in const contexts, such as to put
+  overload sets into a `static` of `&(dyn Fn(u8) -> u8 + Sync)`s.
+
+  Placing `#[warn_deref_overlap]` before the name warns about overloads whose argument
+  types only differ by one being a common `Deref` target of the other's, as with
+  `&String` and `&str`, or `Vec<T>` and `&[T]`. Arguments aren't coerced when picking
+  an overload, so `my_func(&string)` would call the `&String` one, which may come as
+  a surprise. The warning is reported as a use of a deprecated item.
+
+  Placing `#[signatures]` before the name also declares `my_func::SIGNATURES`, which
+  lists the signatures of the overloads as strings, such as
+  `"my_func(x: usize, y: &str) -> f32"`, to be shown in diagnostics.
+
+  Prefixing the name with `callable`, as in `overloadable!(callable pub my_func as ...)`,
+  additionally declares a marker trait named after it, here `MyFuncCallable`, which
+  only the struct implements. Generic code can then be bounded by the overload set,
+  as in `F: MyFuncCallable + Fn(usize, &str) -> f32`.
+
+  Similarly, prefixing the name with `dyn` declares an object-safe trait, here
+  `MyFuncDyn`, which the struct implements with a method for each overload that has a
+  function pointer accessor. These are named the same way, so the first overload above
+  becomes `call_usize_ref_str`, and a `Box<dyn MyFuncDyn>` can be called through them.
+  When the argument types are only known at runtime, `dyn MyFuncDyn` also has a
+  `try_call` method, which takes the arguments as a tuple and picks the overload by
+  their type and the requested return type, as in
+  `my_func.try_call::<_, f32>((1usize, "a"))`. It returns a `MyFuncNoMatchError`
+  describing both types if no such overload exists, whereas `call_any` panics with
+  that description. Since these go through `Any`, the types must be `'static`.
+
+  The struct is hidden from the documentation, unless the name is prefixed with
+  `documented`. It is then documented with a list of the overloads' signatures, each
+  followed by its doc comments, after any docs written before the name. Aliases can
+  be given to it for searches with `#[doc(alias = "...")]` before the name, which is
+  rejected for hidden structs, since it would have no effect.
+
+  A `#[cfg(...)]` attribute on an overload applies to all of the impls generated for
+  it. The same signature may be written several times with different `#[cfg(...)]`s,
+  as in `#[cfg(unix)] fn(x: u8) -> u8` and `#[cfg(not(unix))] fn(x: u8) -> u8`, to give
+  it a body for each configuration, in which case it is an error for none or more
+  than one of them to be enabled. `#[inline]` and `#[cold]` are applied to each of
+  the `call`, `call_mut` and `call_once` methods, which are all marked `#[inline]`
+  unless the overload specifies its own inlining, such as `#[inline(always)]`. This
+  includes hints applied through `#[cfg_attr(...)]`, as in
+  `#[cfg_attr(feature = "fast", inline(always))]`, in which case the methods are only
+  marked `#[inline]` when the predicate doesn't hold. Other attributes are only
+  applied to the `call` method. `#[must_use]` is rejected, as it has no effect on
+  calls through the `Fn*` traits.
+
+  Neither can calls through the `Fn*` traits be reported as deprecated, so a
+  `#[deprecated]` overload instead gets a method of its own, named after its argument
+  types like the function pointer accessors, as in `my_func.call_i32(1)`, which is
+  deprecated along with those accessors. Member overloads are deprecated as usual.
+
+  Since the struct can't be given a turbofish, an overload set with generic overloads
+  gets a `with` method instead, which takes the argument types as a tuple and returns
+  a callable restricted to them, as in `my_func.with::<(u8, _)>()(1, x)`.
+
+  References to the struct are callable through the standard library's blanket
+  `impl<F: Fn<Args>> Fn<Args> for &F`, including for generic overloads, so `&my_func`
+  can be passed wherever an `impl Fn(usize, &str) -> f32` is expected. Implementing
+  the `Fn*` traits for `&my_func` explicitly would conflict with that impl.
+
+  The return types become the `Output` of public trait impls for the struct, so they
+  must be at least as public as it, and a `pub` overload can't return a `pub(crate)`
+  type. The compiler points out such a return type as leaking a private type.
+
+  Each overload without generic parameters also gets an accessor returning it as a
+  plain function pointer, named after its argument types, such as
+  `my_func.as_fn_ptr_usize_ref_str()` for the first overload above. Lifetimes don't
+  count, unless they are bounded, so `fn(x: &[u8]) -> &u8` gets `as_fn_ptr_ref_u8()`,
+  returning a `for<'a> fn(&'a [u8]) -> &'a u8`. Annotating such an overload with
+  `#[coercible_to(fn(usize, &str) -> f32)]` checks at compile time that it coerces to
+  the given function pointer type, with any error pointing at the type.
+  With the `std` or `alloc` feature, such an overload can likewise be erased to a
+  `Box<dyn Fn(usize, &str) -> f32>` through `my_func.boxed_usize_ref_str()`, which
+  works the same for every overload, and with `overloadable_stable`.
+
+  Overloads may also be declared as `async fn`, in which case calling them returns a
+  `Pin<Box<dyn Future<Output = optional_return_type>>>`. This requires the `std`
+  feature, which is enabled by default, or the `alloc` feature.
+
+  Since the `Output` type can't be an `impl Trait`, an overload returning one, such as
+  `fn(x: u8) -> impl Fn(u8) -> u8`, must be annotated with `#[boxed]`. It then returns
+  a `Box<dyn Fn(u8) -> u8>` holding its body's value instead. This also requires the
+  `std` or `alloc` feature.
+
+  The generated code only names items through absolute paths, such as
+  `::core::ops::Fn`, so it is unaffected by shadowed prelude names or
+  `#[no_implicit_prelude]`, and can be used in `#![no_std]` crates. Those using
+  `async` or `#[boxed]` overloads can disable the default features in favour of
+  `alloc`. `Box` is named through a hidden module declaring `extern crate alloc`, so
+  this works whichever of the features another crate in the graph enables.
+
+  Overloads may diverge, as in `fn(x: &str) -> ! { panic!("{}", x) }`, without
+  `#![feature(never_type)]`, alongside others of the same name which return normally.
+
+  Overloads declared as `unsafe fn` have their body placed in an `unsafe` block. Since
+  the `Fn*` traits cannot have `unsafe` methods, calling such an overload is not itself
+  `unsafe`, unlike with `overloadable_member`, where the generated methods are `unsafe fn`.
+
+  Operators can be overloaded for several right-hand side types in the same way with
+  `overloadable_op`, as in `overloadable_op!(+ for Meters as fn(self, rhs: u32) -> Meters { ... })`,
+  which implements the operator's trait from `core` for each overload.
+
+  The macros are thin wrappers around the `overloadable-codegen` crate, which other
+  procedural macros can use to generate overloads themselves, either from the same
+  syntax or by assembling an `OverloadSet` of `Overload`s.
+*/
+extern crate proc_macro;
+use self::proc_macro::TokenStream;
 
 ///
 /// Overloadable function macro. Please read the top level documentation for this crate
@@ -405,21 +297,32 @@ fn gen_trait_fn_decls<T: IntoIterator<Item = ParsedFnDef>>(
 ///
 #[proc_macro]
 pub fn overloadable(input: TokenStream) -> TokenStream {
-    let OverloadableGlobal { vis, name, fns, .. } = parse_macro_input!(input as OverloadableGlobal);
-    let name = &name;
-    let struct_decl = quote_spanned! { name.span() =>
-        #[doc(hidden)]
-        #[allow(non_camel_case_types)]
-        #[allow(dead_code)]
-        #vis struct #name;
-    };
-    let fn_decls = gen_fn_decls(fns, name).unwrap();
-
-    let expanded = quote! {
-        #struct_decl
-        #(#fn_decls)*
-    };
-    TokenStream::from(expanded)
+    TokenStream::from(overloadable_codegen::overloadable(input.into()))
+}
+
+///
+/// Overloadable function macro for stable rust. This has the same syntax as `overloadable`,
+/// but instead of implementing the `Fn*` traits, each overload implements a hidden generic
+/// trait, and the struct gets an inherent `call` method taking the arguments as a tuple.
+///
+/// ## Example:
+/// ```
+/// overloadable::overloadable_stable! {
+///     my_func as
+///     fn(x: usize) -> usize {
+///         x * 2
+///     },
+///     fn(x: &str, y: usize) -> usize {
+///         x.len() + y
+///     },
+/// }
+/// assert_eq!(my_func.call((2,)), 4);
+/// assert_eq!(my_func.call(("abc", 1)), 4);
+/// ```
+///
+#[proc_macro]
+pub fn overloadable_stable(input: TokenStream) -> TokenStream {
+    TokenStream::from(overloadable_codegen::overloadable_stable(input.into()))
 }
 
 ///
@@ -442,19 +345,280 @@ pub fn overloadable(input: TokenStream) -> TokenStream {
 /// }
 /// ```
 ///
+/// Associated functions may be marked `const`, which makes the generated trait a
+/// `const trait` so that they can be called in const contexts. This requires
+/// `#![feature(const_trait_impl)]`. They may take const generics as well, so that
+/// `const fn<T, const N: usize>(x: &[T; N]) -> usize { N }` can give an array length.
+///
+/// Generic structs are supported by declaring their generics before the `::`, as in
+/// `Wrapper<T>::get as fn(&self) -> &T { &self.0 }`.
+///
+/// Several structs can be given the same overloads by listing them, as in
+/// `(Meters, Feet)::scale as ...`. The traits are then named after the first one and
+/// implemented for each of them, with the bodies checked against each struct in turn,
+/// so they should only rely on what all of them provide, such as through `Self`.
+/// Generics are only supported for a single struct.
+///
+/// A `where` clause after the function name, as in `Wrapper<T>::get where T: Clone as ...`,
+/// bounds the struct's generics for every overload. Predicates in an overload's own
+/// `where` clause which only mention the struct's generics are instead applied to
+/// that overload's impl, so the overload is only available when they hold.
+/// The struct's generics are in scope in the signature and body of every overload,
+/// alongside any of its own, as in `fn<U: Into<T>>(&mut self, extra: U) -> T`.
+///
+/// The trait of an overload taking `self` by value has `Sized` as a supertrait, since
+/// the receiver has to be moved, while those of other overloads, including ones taking
+/// `&self`, `&mut self`, `self: Box<Self>` or `self: Pin<&mut Self>`, don't. An
+/// overload can be given the supertrait with `#[sized]`, or have it left out with
+/// `#[maybe_unsized]`. Returning `Self` by value doesn't need the supertrait either,
+/// so a constructor such as `fn() -> Self` is declared without it, and `Self` is the
+/// struct in its impl.
+///
+/// Since the overloads become ordinary trait methods, lifetime elision follows the
+/// usual rules, so `fn(&self) -> &str` borrows from `self`, as does
+/// `fn(&mut self) -> &mut Inner` mutably, which makes for field accessors. Likewise,
+/// `impl Trait` return types are supported, and capture the receiver's lifetime as
+/// they would in any other trait method.
+///
+/// `#[must_use]` on an overload is applied to its trait method's declaration, so that
+/// callers are warned when they drop its result.
+///
+/// The generated trait declarations allow the clippy lints commonly tripped by
+/// generated signatures, such as `too_many_arguments`. More can be allowed with
+/// `#[clippy_allow(...)]`, as in `#[clippy_allow(ptr_arg)]`, which applies to the
+/// generated code but not to the body. This works the same way in `overloadable`.
+///
+/// Associated consts can be declared among the overloads, as in
+/// `const DEFAULT: usize = 3`. These are placed on a trait of their own, named
+/// after the struct and the function, such as `FooGetConsts`, so that they are
+/// imported along with the overloads.
+///
+/// The visibility preceding the struct name, as in `pub(crate) Foo::get as ...`, is
+/// that of the generated traits. It is independent of the struct's own visibility,
+/// since the struct is declared elsewhere, so a `pub` struct can be given overloads
+/// which are only usable within its crate.
+///
+/// A visibility written before an individual `fn` applies to the trait generated
+/// for that overload instead of the one preceding the struct name.
+///
+/// An ABI may also be given, as in `extern "C" fn(x: i32) -> i32 { x }`, in which
+/// case it is applied to the generated methods.
+///
+/// Overloads declared as `unsafe fn` become `unsafe` trait methods, which makes them
+/// suitable for raw pointer receivers, as in `unsafe fn(self: *const Self) -> u32`.
+/// These require `#![feature(arbitrary_self_types_pointers)]`.
+///
+/// A receiver's type may also name the overload's generics, as in
+/// `fn<P>(self: Pin<P>) -> u8 where P: Deref<Target = Self>`, which needs
+/// `#![feature(arbitrary_self_types)]`. The type can't be a generic parameter by itself,
+/// as in `self: T`, since the compiler rejects such receivers.
+///
 /// ** NOTE **
 /// This is internally implemented using custom traits, so to have this functionality
 /// carry over, you must use a `use my_mod::*` to import all of the traits defined by
-/// this macro.
+/// this macro. Alternatively, an overload's trait can be given a name to import it by,
+/// as in `fn as MyGetter(&self) -> usize { 1 }`.
+///
+/// The bodies are placed in the same scope as the traits, so overloads can call each
+/// other without any imports. Method calls such as `self.get(1)` pick an overload by
+/// the receiver, but associated function calls such as `Foo::get()` are ambiguous
+/// whenever there is more than one overload, so these should name the overload's
+/// trait instead, as in `<Foo as MyGetter>::get()`. Since only traits are declared,
+/// this also works for types of other crates, such as `String::describe as ...`.
+///
+/// For a type of the same crate, prefixing the invocation with `inherent`, as in
+/// `overloadable_member!(inherent Foo::get as ...)`, also declares the only overload
+/// without a receiver as an inherent associated function, so that `Foo::get(1)` and
+/// `foo.get(1)` can be told apart without naming any trait. It can be combined with
+/// `grouped`, as in `grouped inherent Foo::get as ...`.
+///
+/// Prefixing the invocation with `grouped`, as in
+/// `overloadable_member!(grouped Foo::get as ...)`, additionally collects every
+/// overload into a single trait named after the struct and the function (here
+/// `FooGet`), under a distinct name each, such as `get_ref_self_usize`. Importing
+/// that one trait is enough to call any overload by its distinct name, while the
+/// overloaded name still works with the per-overload traits in scope. `const`
+/// overloads cannot be grouped.
 ///
 #[proc_macro]
 pub fn overloadable_member(input: TokenStream) -> TokenStream {
-    let OverloadableAssociated {
-        vis,
-        struct_name,
-        name,
-        fns,
-        ..
-    } = parse_macro_input!(input as OverloadableAssociated);
-    TokenStream::from(gen_trait_fn_decls(fns, &name, &struct_name, &vis).unwrap())
+    TokenStream::from(overloadable_codegen::overloadable_member(input.into()))
+}
+
+///
+/// An alternative to `overloadable` and `overloadable_member`, which declares the
+/// overloads as ordinary functions sharing a name, either in a module or in an impl.
+///
+/// On a module, the functions become an overload set in that module, as if declared
+/// by `overloadable`. They must all have the same visibility, which the overload set
+/// is given. On an impl, they become overloaded methods of its self type, as if
+/// declared by `overloadable_member`, with each function's visibility applying to its
+/// trait. Either way, any other items are left as they are.
+///
+/// This can't be named `overloadable`, since that name is taken by the function-like macro.
+///
+/// ## Example:
+/// ```
+/// # #![feature(unboxed_closures, fn_traits)]
+/// #[overloadable::overloads]
+/// mod shapes {
+///     pub fn area(side: f32) -> f32 {
+///         side * side
+///     }
+///     pub fn area(width: f32, height: f32) -> f32 {
+///         width * height
+///     }
+/// }
+/// assert_eq!(shapes::area(2.0), 4.0);
+/// assert_eq!(shapes::area(2.0, 3.0), 6.0);
+/// ```
+///
+#[proc_macro_attribute]
+pub fn overloads(args: TokenStream, input: TokenStream) -> TokenStream {
+    TokenStream::from(overloadable_codegen::overloads(args.into(), input.into()))
+}
+
+///
+/// Calls an overload set with the given arguments, as in `call!(my_func, 1, "a")`,
+/// regardless of which macro declared it. This expands to `my_func.call((1, "a"))`,
+/// which is `Fn::call` for `overloadable`, and the inherent `call` method for
+/// `overloadable_stable`. Arguments are evaluated from left to right.
+///
+/// ## Example:
+/// ```
+/// overloadable::overloadable_stable! {
+///     my_func as
+///     fn() -> usize {
+///         0
+///     },
+///     fn(x: usize, y: usize) -> usize {
+///         x + y
+///     },
+/// }
+/// assert_eq!(overloadable::call!(my_func), 0);
+/// assert_eq!(overloadable::call!(my_func, 1, 2), 3);
+/// ```
+///
+#[proc_macro]
+pub fn call(input: TokenStream) -> TokenStream {
+    TokenStream::from(overloadable_codegen::call(input.into()))
+}
+
+///
+/// Implements overloads for an existing type, rather than for a struct declared by the
+/// macro, as in `overloadable_for!(MyType as fn(x: u8) -> u8 { x + self.offset })`.
+/// This otherwise works like `overloadable`, with the overloads using any fields of
+/// the type through `self`. Since the type might not be constructible out of thin air,
+/// it gets no function pointer accessors.
+///
+/// A visibility before the type, as in `overloadable_for!(pub MyType as ...)`, is
+/// applied to the generated `with` method.
+///
+/// The type may be given generic arguments, as in
+/// `overloadable_for!(Wrapper<u8> as ...)`, as long as they are concrete, since the
+/// overloads can't name the type's parameters.
+///
+/// ## Example:
+/// ```
+/// #![feature(unboxed_closures, fn_traits)]
+/// pub struct Greeter {
+///     greeting: &'static str,
+/// }
+///
+/// overloadable::overloadable_for! {
+///     Greeter as
+///     fn(name: &str) -> String => format!("{}, {}!", self.greeting, name),
+///     fn(count: usize) -> String => self.greeting.repeat(count),
+/// }
+///
+/// let greeter = Greeter { greeting: "Hello" };
+/// assert_eq!(greeter("world"), "Hello, world!");
+/// assert_eq!(greeter(2), "HelloHello");
+/// ```
+///
+#[proc_macro]
+pub fn overloadable_for(input: TokenStream) -> TokenStream {
+    TokenStream::from(overloadable_codegen::overloadable_for(input.into()))
+}
+
+///
+/// Adds overloads to a struct declared by an earlier `overloadable` invocation, as in
+/// `overloadable_extend!(my_func as fn(x: f32) -> f32 { x * 2.0 })`, so that a large
+/// overload set can be split across modules. The struct only has to be in scope, and
+/// isn't declared again, so an error about it not being found means the name is wrong
+/// or needs importing. Since its fields aren't known here, the new overloads get no
+/// function pointer accessors, and generic ones can't be picked through `with`.
+///
+/// ## Example:
+/// ```
+/// #![feature(unboxed_closures, fn_traits)]
+/// overloadable::overloadable! {
+///     pub area as
+///     fn(side: f32) -> f32 => side * side,
+///     fn(width: f32, height: f32) -> f32 => width * height,
+/// }
+///
+/// mod circles {
+///     use super::area;
+///
+///     overloadable::overloadable_extend! {
+///         area as
+///         fn(radius: f64) -> f64 => std::f64::consts::PI * radius * radius,
+///     }
+/// }
+///
+/// fn main() {
+///     assert_eq!(area(2.0f32, 3.0f32), 6.0);
+///     assert_eq!(area(1.0f64), std::f64::consts::PI);
+/// }
+/// ```
+///
+#[proc_macro]
+pub fn overloadable_extend(input: TokenStream) -> TokenStream {
+    TokenStream::from(overloadable_codegen::overloadable_extend(input.into()))
+}
+
+///
+/// Implements an operator's trait once for each overload, so that it can be used with
+/// several right-hand side types. The operator comes first, followed by the type
+/// implementing it, as in `+ for Meters as ...`, after which the overloads are written
+/// as in `overloadable_member`, taking the operands in the way the trait's method does:
+///
+/// - `+`, `-`, `*`, `/`, `%`, `&`, `|`, `^`, `<<` and `>>` take `self` and a right-hand
+///   side, as in `fn(self, rhs: u8) -> Meters`, which implements `Add<u8>`.
+/// - Their compound assignments, such as `+=`, take `&mut self` and a right-hand side,
+///   as in `fn(&mut self, rhs: u8)`, which implements `AddAssign<u8>`.
+/// - `==` and `<` take `&self` and a reference to the right-hand side, implementing
+///   `PartialEq::eq` and `PartialOrd::partial_cmp` respectively, so that
+///   `fn(&self, other: &u8) -> bool` under `==` implements `PartialEq<u8>`.
+/// - `-` and `!` without a right-hand side, as in `fn(self) -> Meters`, implement `Neg`
+///   and `Not`.
+///
+/// Generics of an overload are those of its impl, so they may also be used in the type,
+/// as in `+ for Wrapper<T> as fn<T>(self, rhs: T) -> T { ... }`.
+///
+/// ## Example:
+/// ```
+/// #[derive(Debug)]
+/// struct Meters(f64);
+///
+/// overloadable::overloadable_op! {
+///     + for Meters as
+///     fn(self, rhs: Meters) -> Meters => Meters(self.0 + rhs.0),
+///     fn(self, rhs: f64) -> Meters => Meters(self.0 + rhs),
+/// }
+///
+/// overloadable::overloadable_op! {
+///     == for Meters as
+///     fn(&self, other: &Meters) -> bool => self.0 == other.0,
+///     fn(&self, other: &f64) -> bool => self.0 == *other,
+/// }
+///
+/// assert_eq!(Meters(1.0) + Meters(2.0), Meters(3.0));
+/// assert_eq!(Meters(1.0) + 2.0, 3.0);
+/// ```
+///
+#[proc_macro]
+pub fn overloadable_op(input: TokenStream) -> TokenStream {
+    TokenStream::from(overloadable_codegen::overloadable_op(input.into()))
 }
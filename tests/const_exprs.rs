@@ -0,0 +1,32 @@
+#![feature(unboxed_closures, fn_traits, generic_const_exprs)]
+#![allow(incomplete_features)]
+
+overloadable::overloadable! {
+    split_last as
+    fn<const N: usize>(x: [u8; N]) -> (u8, [u8; N - 1]) where [(); N - 1]: {
+        let mut rest = [0; N - 1];
+        rest.copy_from_slice(&x[..N - 1]);
+        (x[N - 1], rest)
+    },
+    fn<T: Copy + Default, const N: usize>(x: [T; N], fill: T) -> [T; N + 1] where [(); N + 1]: {
+        let mut grown = [fill; N + 1];
+        grown[..N].copy_from_slice(&x);
+        grown
+    }
+}
+
+fn call_mut<F: FnMut([u8; 2]) -> (u8, [u8; 1])>(mut f: F) -> (u8, [u8; 1]) {
+    f([4, 5])
+}
+
+fn call_once<F: FnOnce([u8; 1]) -> (u8, [u8; 0])>(f: F) -> (u8, [u8; 0]) {
+    f([6])
+}
+
+#[test]
+fn const_expression_bounds() {
+    assert_eq!(split_last([1, 2, 3]), (3, [1, 2]));
+    assert_eq!(split_last([1u16, 2], 0), [1, 2, 0]);
+    assert_eq!(call_mut(split_last), (5, [4]));
+    assert_eq!(call_once(split_last), (6, []));
+}
@@ -1,4 +1,6 @@
-#![feature(unboxed_closures, fn_traits)]
+#![feature(unboxed_closures, fn_traits, const_trait_impl, arbitrary_self_types_pointers)]
+// The original examples at the top are only checked to compile, as they were written.
+#![allow(dead_code, clippy::redundant_slicing)]
 use std::fmt::Debug;
 overloadable::overloadable! {
     pub(crate) func_name as
@@ -68,3 +70,2113 @@ overloadable::overloadable_member! {
         }
     }
 }
+
+overloadable::overloadable! {
+    async_func as
+    async fn(x: usize) -> usize {
+        x * 2
+    },
+    async fn(x: String, y: String) -> String {
+        format!("{}{}", x, y)
+    }
+}
+
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+    loop {
+        if let std::task::Poll::Ready(x) = future.as_mut().poll(&mut context) {
+            return x;
+        }
+    }
+}
+
+#[test]
+fn async_overloads() {
+    block_on(async {
+        assert_eq!(async_func(21).await, 42);
+        let a = String::from("abc");
+        let b = String::from("def");
+        assert_eq!(async_func(a, b).await, "abcdef");
+    });
+}
+
+overloadable::overloadable! {
+    read_ptr as
+    unsafe fn(x: *const usize) -> usize {
+        *x
+    },
+    unsafe fn(x: *const u8, y: usize) -> u8 {
+        *x.add(y)
+    }
+}
+
+pub struct RawReader;
+
+overloadable::overloadable_member! {
+    RawReader::read as
+    unsafe fn(x: *const u32) -> u32 {
+        *x
+    }
+}
+
+#[test]
+fn unsafe_overloads() {
+    let x = 5usize;
+    assert_eq!(read_ptr(&x as *const usize), 5);
+    let bytes = [1u8, 2, 3];
+    assert_eq!(read_ptr(bytes.as_ptr(), 2), 3);
+    let y = 7u32;
+    assert_eq!(unsafe { RawReader::read(&y as *const u32) }, 7);
+}
+
+pub struct RawCell(u32);
+
+overloadable::overloadable_member! {
+    RawCell::peek as
+    unsafe fn(self: *const Self) -> u32 {
+        (*self).0
+    },
+    unsafe fn(self: *mut Self, x: u32) {
+        (*self).0 = x
+    }
+}
+
+#[test]
+fn unsafe_raw_pointer_receivers() {
+    let mut cell = RawCell(3);
+    let ptr: *mut RawCell = &mut cell;
+    unsafe {
+        ptr.peek(5);
+        assert_eq!((ptr as *const RawCell).peek(), 5);
+    }
+}
+
+pub struct ConstFoo;
+
+overloadable::overloadable_member! {
+    ConstFoo::double as
+    const fn(x: usize) -> usize {
+        x * 2
+    }
+}
+
+const DOUBLED: usize = ConstFoo::double(21);
+
+#[test]
+fn const_member_overloads() {
+    assert_eq!(DOUBLED, 42);
+    assert_eq!([0u8; ConstFoo::double(2)].len(), 4);
+}
+
+pub struct ConstLen;
+
+overloadable::overloadable_member! {
+    ConstLen::len as
+    const fn<T, const N: usize>(x: &[T; N]) -> usize {
+        N
+    }
+}
+
+const ITEMS: [u16; ConstLen::len(&[(); 3])] = [7; ConstLen::len(&[(); 3])];
+
+#[test]
+fn const_generic_const_members() {
+    assert_eq!(ITEMS.len(), 3);
+    assert_eq!([0u8; ConstLen::len(&[1u32, 2])], [0, 0]);
+    assert_eq!(ConstLen::len(&["a"; 5]), 5);
+}
+
+pub struct Callbacks;
+
+overloadable::overloadable_member! {
+    Callbacks::callback as
+    extern "C" fn(x: i32) -> i32 {
+        x + 1
+    }
+}
+
+#[test]
+fn extern_abi_member() {
+    let callback: extern "C" fn(i32) -> i32 = Callbacks::callback;
+    assert_eq!(callback(1), 2);
+}
+
+overloadable::overloadable! {
+    describe as
+    fn(x: impl std::fmt::Display) -> String {
+        format!("{}", x)
+    },
+    fn<T: Debug>(x: T, y: impl std::fmt::Display, z: &impl Debug) -> String {
+        format!("{:?} {} {:?}", x, y, z)
+    }
+}
+
+pub struct Describer;
+
+overloadable::overloadable_member! {
+    Describer::describe as
+    fn(&self, x: impl std::fmt::Display, y: impl Debug) -> String {
+        format!("{} {:?}", x, y)
+    }
+}
+
+#[test]
+fn impl_trait_arguments() {
+    assert_eq!(describe(5), "5");
+    assert_eq!(Describer.describe(1, "b"), "1 \"b\"");
+    assert_eq!(describe("a", 1.5, &[1, 2]), "\"a\" 1.5 [1, 2]");
+}
+
+overloadable::overloadable! {
+    sum_items as
+    fn(x: impl Iterator<Item = u8>) -> u32 => x.map(u32::from).sum(),
+    fn(x: impl Iterator<Item = &'a u8>, y: u8) -> usize => x.filter(|&&b| b == y).count(),
+    fn(x: impl Iterator<Item = &u16>, y: u16, z: u16) -> u16 => x.sum::<u16>() + y + z,
+    fn(x: impl IntoIterator<Item = &u8>, keep: impl Fn(&u8) -> bool, y: u8, z: u8) -> u8 {
+        x.into_iter().filter(|b| keep(b)).sum::<u8>() + y + z
+    },
+}
+
+#[test]
+fn impl_trait_lifetimes() {
+    assert_eq!(sum_items(vec![1u8, 2].into_iter()), 3);
+    assert_eq!(sum_items([1u8, 2, 2].iter(), 2u8), 2);
+    assert_eq!(sum_items([1u16, 2].iter(), 1u16, 1u16), 5);
+    assert_eq!(sum_items(&[1u8, 2, 3], |b: &u8| *b > 1, 0u8, 1u8), 6);
+}
+
+overloadable::overloadable! {
+    size_of_type as
+    #[phantom]
+    fn<T>() -> usize {
+        std::mem::size_of::<T>()
+    },
+    #[phantom]
+    fn<T, U>(scale: usize) -> usize {
+        scale * (std::mem::size_of::<T>() + std::mem::size_of::<U>())
+    }
+}
+
+#[test]
+fn phantom_generics() {
+    use std::marker::PhantomData;
+    assert_eq!(size_of_type(PhantomData::<(u32,)>), 4);
+    assert_eq!(size_of_type(2, PhantomData::<(u8, u16)>), 6);
+}
+
+pub struct Receivers(usize);
+
+overloadable::overloadable_member! {
+    Receivers::receiver as
+    fn(self: Box<Self>, x: usize) -> usize {
+        self.0 + x
+    },
+    fn(self: std::pin::Pin<&mut Self>) -> usize {
+        self.get_mut().0 += 1;
+        1
+    },
+    fn(&self) -> usize {
+        self.0
+    },
+    fn<'a>(&'a mut self, x: usize) -> &'a mut usize {
+        self.0 += x;
+        &mut self.0
+    },
+    fn(mut self) -> usize {
+        self.0 *= 2;
+        self.0
+    },
+    fn(self: std::rc::Rc<Self>) -> usize {
+        std::rc::Rc::strong_count(&self)
+    }
+}
+
+#[test]
+fn receivers() {
+    assert_eq!(Box::new(Receivers(1)).receiver(2), 3);
+    let mut value = Receivers(0);
+    std::pin::Pin::new(&mut value).receiver();
+    let by_ref = &value;
+    assert_eq!(by_ref.receiver(), 1);
+    let by_mut = &mut value;
+    assert_eq!(*by_mut.receiver(2), 3);
+    assert_eq!(value.receiver(), 6);
+    assert_eq!(std::rc::Rc::new(Receivers(0)).receiver(), 1);
+}
+
+mod visibility {
+    pub struct Visible;
+
+    overloadable::overloadable_member! {
+        pub Visible::get as
+        fn(&self) -> usize {
+            1
+        },
+        pub(self) fn(self: Box<Self>) -> usize {
+            2
+        }
+    }
+
+    pub fn private_get() -> usize {
+        Box::new(Visible).get()
+    }
+}
+
+#[test]
+fn per_overload_visibility() {
+    use visibility::*;
+    let visible = &Visible;
+    assert_eq!(visible.get(), 1);
+    assert_eq!(private_get(), 2);
+}
+
+overloadable::overloadable! {
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    cloneable as
+    fn(x: u8) -> u8 {
+        x + 1
+    }
+}
+
+#[test]
+fn struct_attributes() {
+    let original = cloneable;
+    #[allow(clippy::clone_on_copy)]
+    let copy = original.clone();
+    assert_eq!(copy, original);
+    assert_eq!(copy(1), 2);
+    assert_eq!(format!("{:?}", copy), "cloneable");
+}
+
+overloadable::overloadable! {
+    #[derive(Default)]
+    derived_default as
+    fn(x: u8) -> u8 {
+        x
+    }
+}
+
+fn make<T: Default>() -> T {
+    T::default()
+}
+
+#[test]
+fn constructors() {
+    let by_new = cloneable::new();
+    let by_default: cloneable = make();
+    let moved = move |x| by_new(x) + by_default(x);
+    assert_eq!(moved(1), 4);
+    assert_eq!(make::<derived_default>()(3), 3);
+}
+
+pub struct Wrapper<T>(T);
+
+overloadable::overloadable_member! {
+    Wrapper<T>::get as
+    fn(&self) -> &T {
+        &self.0
+    },
+    fn(self: Box<Self>) -> usize {
+        std::mem::size_of::<T>()
+    }
+}
+
+#[test]
+fn generic_structs() {
+    let wrapper = &Wrapper(3u16);
+    assert_eq!(*wrapper.get(), 3);
+    assert_eq!(Box::new(Wrapper(3u16)).get(), 2);
+}
+
+pub struct Named(String);
+
+overloadable::overloadable_member! {
+    Named::name as
+    fn(&self) -> &str {
+        &self.0
+    },
+    fn(self: Box<Self>, prefix: &str) -> String {
+        format!("{}{}", prefix, self.0)
+    }
+}
+
+#[test]
+fn elided_receiver_lifetimes() {
+    let named = &Named(String::from("abc"));
+    assert_eq!(named.name(), "abc");
+    assert_eq!(Box::new(Named(String::from("c"))).name("ab"), "abc");
+}
+
+overloadable::overloadable! {
+    configured as
+    #[cfg(test)]
+    fn(x: u8) -> u8 {
+        x
+    },
+    #[cfg(not(test))]
+    fn(x: u16) -> u16 {
+        this_function_does_not_exist(x)
+    }
+}
+
+pub struct Configured;
+
+overloadable::overloadable_member! {
+    Configured::configured as
+    #[cfg(test)]
+    fn(&self) -> u8 {
+        1
+    },
+    #[cfg(not(test))]
+    fn(self) -> u8 {
+        this_function_does_not_exist()
+    }
+}
+
+#[test]
+fn cfg_overloads() {
+    assert_eq!(configured(3), 3);
+    let member = &Configured;
+    assert_eq!(member.configured(), 1);
+}
+
+overloadable::overloadable! {
+    pointed as
+    fn(x: usize) -> usize {
+        x * 2
+    },
+    fn(x: &str, y: usize) -> usize {
+        x.len() + y
+    },
+    fn() {},
+    fn<T: Debug>(x: Vec<T>) -> String {
+        format!("{:?}", x)
+    }
+}
+
+#[test]
+fn fn_pointer_accessors() {
+    let double: fn(usize) -> usize = pointed.as_fn_ptr_usize();
+    assert_eq!(double(2), 4);
+    let table = [pointed.as_fn_ptr_ref_str_usize()];
+    assert_eq!(table[0]("abc", 1), 4);
+    pointed.as_fn_ptr_unit()();
+}
+
+pub struct Located;
+
+overloadable::overloadable_member! {
+    Located::caller_line as
+    #[track_caller]
+    fn() -> u32 {
+        std::panic::Location::caller().line()
+    }
+}
+
+#[test]
+fn track_caller_member() {
+    assert_eq!(Located::caller_line(), line!());
+}
+
+pub struct Bytes(Vec<u8>);
+
+overloadable::overloadable_member! {
+    Bytes::iter as
+    fn(&self) -> impl Iterator<Item = u8> + '_ {
+        self.0.iter().copied()
+    },
+    fn<'a, T>(self: Box<Self>, extra: &'a [T]) -> impl Iterator<Item = u8> + 'a
+    where
+        T: Into<u8> + Copy,
+    {
+        self.0.into_iter().chain(extra.iter().map(|x| (*x).into()))
+    }
+}
+
+#[test]
+fn impl_trait_returns() {
+    let bytes = &Bytes(vec![1, 2]);
+    assert_eq!(bytes.iter().collect::<Vec<_>>(), [1, 2]);
+    let owned = Box::new(Bytes(vec![1]));
+    assert_eq!(owned.iter(&[true, false][..]).sum::<u8>(), 2);
+}
+
+mod named_traits {
+    pub struct Getter;
+
+    overloadable::overloadable_member! {
+        pub Getter::get as
+        fn as MyGetter(&self) -> usize {
+            1
+        },
+        fn as MyBoxedGetter(self: Box<Self>) -> usize {
+            2
+        }
+    }
+}
+
+#[test]
+fn named_member_traits() {
+    use named_traits::{Getter, MyBoxedGetter, MyGetter};
+    let getter = &Getter;
+    assert_eq!(getter.get(), 1);
+    assert_eq!(Box::new(Getter).get(), 2);
+}
+
+mod grouped_traits {
+    pub struct Counter(pub usize);
+
+    overloadable::overloadable_member! {
+        grouped pub Counter::count as
+        fn(&self) -> usize {
+            self.0
+        },
+        fn(&mut self, by: usize) {
+            self.0 += by;
+        },
+        fn(self: Box<Self>) -> usize {
+            self.0 * 10
+        }
+    }
+}
+
+#[test]
+fn grouped_member_traits() {
+    {
+        use grouped_traits::{Counter, CounterCount};
+        let mut counter = Counter(2);
+        assert_eq!(counter.count_ref_self(), 2);
+        counter.count_ref_mut_self_usize(1);
+        assert_eq!(counter.count_ref_self(), 3);
+        assert_eq!(Box::new(counter).count_self_box_self(), 30);
+    }
+    use grouped_traits::*;
+    let mut counter = Counter(4);
+    let by_mut = &mut counter;
+    by_mut.count(1);
+    let by_ref = &counter;
+    assert_eq!(by_ref.count(), 5);
+    assert_eq!(Box::new(Counter(1)).count(), 10);
+}
+
+overloadable::overloadable! {
+    array_len as
+    fn<const N: usize>(x: [u16; N]) -> usize {
+        x.len() + N
+    },
+    fn(x: [u8; 4]) -> &'static str {
+        let _ = x;
+        "four"
+    },
+    fn(x: [u8; 8]) -> &'static str {
+        let _ = x;
+        "eight"
+    },
+    fn<T: Copy, const N: usize>(x: [T; N], y: T) -> [T; N] where T: PartialEq {
+        let mut x = x;
+        x[N - 1] = y;
+        x
+    }
+}
+
+pub struct Arrays;
+
+overloadable::overloadable_member! {
+    Arrays::sum as
+    fn<const N: usize>(x: [u8; N]) -> usize {
+        x.iter().map(|&x| x as usize).sum::<usize>() + N
+    }
+}
+
+#[test]
+fn const_generics() {
+    assert_eq!(array_len([0u16; 3]), 6);
+    assert_eq!(array_len([0u8; 4]), "four");
+    assert_eq!(array_len([0u8; 8]), "eight");
+    assert_eq!(array_len([1, 2], 3), [1, 3]);
+    assert_eq!(Arrays::sum([1, 2]), 5);
+}
+
+overloadable::overloadable! {
+    callable pub marked_func as
+    fn(x: u8) -> u8 {
+        x + 1
+    },
+    fn(x: &str) -> usize {
+        x.len()
+    }
+}
+
+fn call_marked<F: MarkedFuncCallable + Fn(u8) -> u8 + for<'a> Fn(&'a str) -> usize>(f: F) -> usize {
+    f(1) as usize + f("abc")
+}
+
+#[test]
+fn callable_marker() {
+    assert_eq!(call_marked(marked_func), 5);
+}
+
+overloadable::overloadable! {
+    increment as
+    fn(mut x: usize) -> usize {
+        x += 1;
+        x
+    },
+    fn(mut x: String, (mut y, z): (usize, usize)) -> String {
+        x.push('!');
+        y += z;
+        format!("{}{}", x, y)
+    }
+}
+
+pub struct Incrementer;
+
+overloadable::overloadable_member! {
+    Incrementer::increment as
+    fn(&self, mut x: usize) -> usize {
+        x += 2;
+        x
+    }
+}
+
+#[test]
+fn mut_parameters() {
+    assert_eq!(increment(1), 2);
+    assert_eq!(increment(String::from("a"), (1, 2)), "a!3");
+    assert_eq!(Incrementer.increment(1), 3);
+}
+
+overloadable::overloadable! {
+    tuple_ends as
+    fn((first, .., last): (u8, u8, u8)) -> (u8, u8) {
+        (first, last)
+    },
+    fn(((a, _), [b, .., c]): ((u16, u16), [u16; 4]), &d: &u16) -> u16 {
+        a + b + c + d
+    }
+}
+
+#[test]
+fn tuple_patterns() {
+    assert_eq!(tuple_ends((1, 2, 3)), (1, 3));
+    assert_eq!(tuple_ends(((1, 2), [3, 4, 5, 6]), &7), 17);
+}
+
+overloadable::overloadable! {
+    hot_add as
+    #[inline(always)]
+    fn(x: u64, y: u64) -> u64 {
+        x.wrapping_add(y)
+    },
+    fn(x: u32) -> u64 {
+        x as u64
+    }
+}
+
+fn fold_with<F: FnMut(u64, u64) -> u64>(mut f: F, n: u64) -> u64 {
+    (0..n).fold(0, &mut f)
+}
+
+#[test]
+fn inlined_calls() {
+    let total = fold_with(hot_add, 100_000);
+    assert_eq!(total, (0..100_000).sum::<u64>());
+    assert_eq!(std::hint::black_box(hot_add)(3u32), 3);
+}
+
+pub struct Pair<T>(T, T);
+
+overloadable::overloadable_member! {
+    Pair<T>::first where T: Copy as
+    fn(&self) -> T {
+        self.0
+    },
+    fn<U>(&mut self, other: U) -> (T, U) where T: Default, U: Copy {
+        (std::mem::take(&mut self.0), other)
+    }
+}
+
+pub struct Labelled<T>(T);
+
+overloadable::overloadable_member! {
+    Labelled<T>::describe as
+    fn(&self) -> String {
+        String::from("opaque")
+    },
+    fn(self: Box<Self>) -> String where T: Debug {
+        format!("{:?}", self.0)
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct Opaque(u8);
+
+#[test]
+fn struct_where_clauses() {
+    let pair = &Pair(1u8, 2);
+    assert_eq!(pair.first(), 1);
+    let by_mut = &mut Pair(Opaque(3), Opaque(4));
+    assert_eq!(by_mut.first(5).1, 5);
+    assert_eq!(by_mut.0 .0, 0);
+    // Without `Debug`, the boxed overload isn't implemented, so the call
+    // resolves to the one taking `&self` through the box instead.
+    assert_eq!(Box::new(Labelled(1u8)).describe(), "1");
+    let boxed = Box::new(Labelled(Opaque(1)));
+    assert_eq!(boxed.describe(), "opaque");
+}
+
+overloadable::overloadable! {
+    adder as
+    #[boxed]
+    fn(x: u8) -> impl Fn(u8) -> u8 {
+        move |y| x + y
+    },
+    #[boxed]
+    fn(x: String) -> impl Iterator<Item = char> {
+        x.into_bytes().into_iter().map(char::from)
+    }
+}
+
+#[test]
+fn boxed_returns() {
+    let add_two = adder(2);
+    assert_eq!(add_two(3), 5);
+    assert_eq!(adder(String::from("ab")).collect::<String>(), "ab");
+}
+
+overloadable::overloadable! {
+    called as
+    fn() -> &'static str {
+        "none"
+    },
+    fn(x: String, y: String) -> String {
+        x + &y
+    }
+}
+
+#[test]
+fn call_macro() {
+    use overloadable::call;
+    assert_eq!(call!(called), "none");
+    let mut order = Vec::new();
+    let mut next = |s: &str| {
+        order.push(s.to_owned());
+        s.to_owned()
+    };
+    assert_eq!(call!(called, next("a"), next("b")), "ab");
+    assert_eq!(order, ["a", "b"]);
+}
+
+overloadable::overloadable! {
+    apply_str as
+    fn<F>(f: F, x: &str) -> String where F: for<'a> Fn(&'a str) -> &'a str {
+        f(x).to_owned()
+    },
+    fn<F>(f: F, x: &str, y: &str) -> String where for<'a> F: Fn(&'a str, &'a str) -> &'a str {
+        f(x, y).to_owned()
+    }
+}
+
+pub struct Trimmer<'s>(&'s str);
+
+overloadable::overloadable_member! {
+    Trimmer<'s>::apply as
+    fn<F>(&self, f: F) -> &'s str where F: for<'a> Fn(&'a str) -> &'a str {
+        f(self.0)
+    }
+}
+
+type StrMap = fn(&str) -> &str;
+
+fn via_fn_mut<F: FnMut(StrMap, &'static str) -> String>(mut f: F) -> String {
+    f(str::trim, " d ")
+}
+
+fn via_fn_once<F: FnOnce(StrMap, &'static str) -> String>(f: F) -> String {
+    f(str::trim_start, " e")
+}
+
+#[test]
+fn higher_ranked_bounds() {
+    assert_eq!(apply_str(str::trim, " a "), "a");
+    assert_eq!(apply_str(|x: &str, _: &str| x, "b", "c"), "b");
+    assert_eq!(via_fn_mut(apply_str), "d");
+    assert_eq!(via_fn_once(apply_str), "e");
+    assert_eq!(Trimmer(" f ").apply(str::trim), "f");
+}
+
+overloadable::overloadable! {
+    #[derive(Debug, PartialEq)]
+    itself as
+    fn() -> Self {
+        Self
+    },
+    fn(x: Self, y: Vec<Self>) -> usize where Self: PartialEq {
+        y.iter().filter(|&y| *y == x).count()
+    },
+    fn(x: u8) -> Option<Self> {
+        struct Inner;
+        impl Inner {
+            fn make() -> Self {
+                Inner
+            }
+        }
+        let _inner = Inner::make();
+        if x == 0 { None } else { Some(itself) }
+    }
+}
+
+#[test]
+fn self_type() {
+    assert_eq!(itself(), itself);
+    assert_eq!(itself(itself, vec![itself, itself]), 2);
+    assert_eq!(itself(1), Some(itself));
+}
+
+overloadable::overloadable! {
+    dyn pub dispatched as
+    fn() -> String {
+        String::from("none")
+    },
+    fn(x: u8) -> String {
+        x.to_string()
+    },
+    fn(x: &str, y: u8) -> String {
+        format!("{}{}", x, y)
+    },
+    fn<T: Debug>(x: T, y: T, z: T) -> String {
+        format!("{:?}{:?}{:?}", x, y, z)
+    },
+    fn(x: &[u8]) -> &[u8] => &x[1..],
+}
+
+#[test]
+fn dyn_dispatch() {
+    let boxed: Box<dyn DispatchedDyn> = Box::new(dispatched);
+    assert_eq!(boxed.call_unit(), "none");
+    assert_eq!(boxed.call_u8(1), "1");
+    assert_eq!(boxed.call_ref_str_u8("a", 2), "a2");
+    assert_eq!(boxed.call_ref_u8(&[1, 2][..]), [2]);
+    let handlers: Vec<Box<dyn DispatchedDyn>> = vec![boxed, Box::new(dispatched::new())];
+    assert_eq!(handlers.iter().map(|h| h.call_u8(3)).collect::<String>(), "33");
+}
+
+#[test]
+fn dyn_try_call() {
+    let boxed: Box<dyn DispatchedDyn> = Box::new(dispatched);
+    assert_eq!(boxed.try_call::<_, String>((4u8,)), Ok(String::from("4")));
+    assert_eq!(boxed.try_call::<_, String>(("b", 5u8)).unwrap(), "b5");
+    assert_eq!(boxed.call_any::<_, String>(()), "none");
+    assert_eq!(boxed.call_any::<_, &[u8]>((&b"ab"[..],)), b"b");
+    let error = boxed.try_call::<_, String>((1u16,)).unwrap_err();
+    assert_eq!(error, DispatchedNoMatchError { arguments: "(u16,)", output: "alloc::string::String" });
+    assert_eq!(error.to_string(), "No overload of `dispatched` takes `(u16,)` and returns `alloc::string::String`.");
+    // The output has to match as well.
+    assert!(boxed.try_call::<_, u8>((4u8,)).is_err());
+}
+
+#[test]
+#[should_panic(expected = "No overload of `dispatched` takes `(bool,)`")]
+fn dyn_call_any_mismatch() {
+    let boxed: Box<dyn DispatchedDyn> = Box::new(dispatched);
+    boxed.call_any::<_, String>((true,));
+}
+
+overloadable::overloadable! {
+    offset as
+    fn(x: u8, #[default(1)] y: u8, #[default(y * 10)] z: u8) -> u8 {
+        x + y + z
+    },
+    fn(x: &str, #[default(String::from("!"))] suffix: String) -> String {
+        format!("{}{}", x, suffix)
+    }
+}
+
+#[test]
+fn default_arguments() {
+    assert_eq!(offset(1), 12);
+    assert_eq!(offset(1, 2), 23);
+    assert_eq!(offset(1, 2, 3), 6);
+    assert_eq!(offset("a"), "a!");
+    assert_eq!(offset("a", String::from("?")), "a?");
+}
+
+mod with_consts {
+    pub struct Repeater(pub &'static str);
+
+    overloadable::overloadable_member! {
+        pub Repeater::repeat as
+        /// How often to repeat by default.
+        const DEFAULT: usize = 3,
+        fn(&self) -> String {
+            self.0.repeat(Self::DEFAULT)
+        },
+        const SEPARATOR: &'static str = "-",
+        fn(self: Box<Self>, count: usize) -> String {
+            vec![self.0; count].join(Self::SEPARATOR)
+        }
+    }
+}
+
+#[test]
+fn member_consts() {
+    use with_consts::*;
+    assert_eq!(Repeater::DEFAULT, 3);
+    assert_eq!(<Repeater as RepeaterRepeatConsts>::SEPARATOR, "-");
+    let repeater = &Repeater("a");
+    assert_eq!(repeater.repeat(), "aaa");
+    assert_eq!(Box::new(Repeater("b")).repeat(2), "b-b");
+}
+
+overloadable::overloadable! {
+    dyn scaled { factor: u32, pub(crate) label: &'static str } as
+    fn(x: u32) -> u32 {
+        x * self.factor
+    },
+    fn(x: &str) -> String {
+        format!("{}: {}", self.label, x)
+    }
+}
+
+#[test]
+fn stateful_overloads() {
+    let triple = scaled { factor: 3, label: "triple" };
+    assert_eq!(triple(2), 6);
+    assert_eq!(triple("x"), "triple: x");
+    let double = scaled::new(2, "double");
+    let boxed: Box<dyn ScaledDyn> = Box::new(double);
+    assert_eq!(boxed.call_u32(4), 8);
+    let moved = move |x| triple(x) + 1;
+    assert_eq!(moved(1), 4);
+}
+
+overloadable::overloadable! {
+    dyn sum_many as
+    fn(a: u8, b: u8, c: u8, d: u8, e: u8, f: u8, g: u8, h: u8) -> u32 {
+        [a, b, c, d, e, f, g, h].iter().map(|&x| x as u32).sum()
+    },
+    fn(a: Vec<Vec<Box<dyn Fn(u8) -> Option<(u8, u8)>>>>) -> Option<Vec<Vec<(u8, u8)>>> {
+        a.into_iter().map(|v| v.into_iter().map(|f| f(0)).collect()).collect()
+    },
+    #[clippy_allow(ptr_arg)]
+    fn(a: &Vec<u8>) -> u32 {
+        a.len() as u32
+    }
+}
+
+pub struct ManyArgs;
+
+overloadable::overloadable_member! {
+    ManyArgs::sum as
+    fn(&self, a: u8, b: u8, c: u8, d: u8, e: u8, f: u8, g: u8, h: u8) -> u32 {
+        sum_many(a, b, c, d, e, f, g, h)
+    },
+    #[clippy_allow(ptr_arg)]
+    fn(self: Box<Self>, a: &Vec<u8>) -> u32 {
+        sum_many(a)
+    }
+}
+
+#[test]
+fn many_arguments() {
+    assert_eq!(sum_many(1, 1, 1, 1, 1, 1, 1, 1), 8);
+    assert_eq!(ManyArgs.sum(1, 2, 3, 4, 5, 6, 7, 8), 36);
+    assert_eq!(Box::new(ManyArgs).sum(&vec![1, 2]), 2);
+}
+
+overloadable::overloadable! {
+    generic_len as
+    fn<T>(x: Vec<T>) -> usize {
+        x.len()
+    },
+    fn<T: AsRef<str>>(x: T, y: usize) -> usize {
+        x.as_ref().len() + y
+    }
+}
+
+fn apply_vec<F: Fn(Vec<u8>) -> usize>(f: F) -> usize {
+    f(vec![1, 2])
+}
+
+fn apply_str_len(f: impl Fn(&str, usize) -> usize) -> usize {
+    f("abc", 1)
+}
+
+#[test]
+fn generic_overloads_by_reference() {
+    let by_ref = &generic_len;
+    assert_eq!(apply_vec(by_ref), 2);
+    assert_eq!(apply_str_len(by_ref), 4);
+    let by_double_ref = &by_ref;
+    assert_eq!(apply_str_len(by_double_ref), 4);
+}
+
+overloadable::overloadable! {
+    widen_into as
+    fn<T: Into<u64>>(x: T) -> u64 {
+        x.into()
+    },
+    fn<'a, T: Into<u64>>(x: T, y: &'a str) -> (u64, &'a str) {
+        (x.into(), y)
+    },
+    fn(x: &str, y: &str, z: &str) -> usize {
+        x.len() + y.len() + z.len()
+    }
+}
+
+#[test]
+fn explicit_instantiation() {
+    // An integer literal would otherwise default to `i32`, which isn't `Into<u64>`.
+    let from_u8 = widen_into.with::<(u8,)>();
+    assert_eq!(from_u8(7), 7);
+    assert_eq!(widen_into.with::<(u32, _)>()(3, "a"), (3, "a"));
+    assert_eq!(widen_into.with::<(&str, &str, &str)>()("a", "b", "c"), 3);
+}
+
+overloadable::overloadable! {
+    /// Halves its argument, or the sum of its arguments.
+    #[doc(alias = "half")]
+    documented pub halve as
+    /// Halves `x`.
+    fn(x: u32) -> u32 {
+        x / 2
+    },
+    /// Halves `x + y`.
+    fn(x: u32, y: u32) -> u32 {
+        (x + y) / 2
+    }
+}
+
+#[test]
+fn documented_overloads() {
+    assert_eq!(halve(8), 4);
+    assert_eq!(halve(3, 5), 4);
+}
+
+overloadable::overloadable! {
+    longer as
+    fn((ref a, ref b): (String, String)) -> usize {
+        // Borrowing from the tuple, rather than moving its elements out.
+        let longest: &String = if a.len() > b.len() { a } else { b };
+        longest.len()
+    },
+    fn(ref mut total: u32, x: u32) -> u32 {
+        *total += x;
+        *total
+    }
+}
+
+#[test]
+fn ref_binding_modes() {
+    assert_eq!(longer((String::from("ab"), String::from("abc"))), 3);
+    assert_eq!(longer(1, 2), 3);
+}
+
+pub struct Counter(u32);
+
+overloadable::overloadable_member! {
+    Counter::count as
+    fn as CounterNew() -> Self {
+        Counter(3)
+    },
+    fn(&self, acc: u32) -> u32 {
+        if acc == self.0 { acc } else { self.count(acc + 1) }
+    },
+    fn(self) -> u32 {
+        // `self.count(0)` would pick this overload again, by its receiver.
+        let this = &self;
+        this.count(0)
+    },
+    fn(self: Box<Self>) -> u32 {
+        // Associated functions are only told apart through their trait's name.
+        <Counter as CounterNew>::count().count() + self.0
+    }
+}
+
+#[test]
+fn member_overloads_call_each_other() {
+    assert_eq!(Counter(2).count(), 2);
+    assert_eq!(Box::new(Counter(1)).count(), 4);
+}
+
+mod shared {
+    pub struct Shared(pub u32);
+
+    overloadable::overloadable_member! {
+        pub(crate) Shared::describe as
+        fn(&self) -> u32 {
+            self.0
+        },
+        fn(self, x: u32) -> u32 {
+            self.0 + x
+        }
+    }
+}
+
+mod uses_shared {
+    use crate::shared::*;
+
+    pub fn describe_both(x: u32) -> (u32, u32) {
+        let shared = &Shared(x);
+        (shared.describe(), Shared(x).describe(1))
+    }
+}
+
+#[test]
+fn crate_visible_member_traits() {
+    assert_eq!(uses_shared::describe_both(2), (2, 3));
+}
+
+pub struct Meters;
+pub struct Feet;
+
+overloadable::overloadable_member! {
+    (Meters, Feet)::scale as
+    const BASE: u32 = 10,
+    fn(&self, x: u32) -> u32 {
+        x * Self::BASE
+    },
+    fn(self) -> u32 {
+        Self::BASE
+    }
+}
+
+#[test]
+fn shared_by_several_structs() {
+    let (meters, feet) = (&Meters, &Feet);
+    assert_eq!(meters.scale(2), 20);
+    assert_eq!(feet.scale(3), 30);
+    assert_eq!(Feet.scale(), 10);
+}
+
+#[overloadable::overloads]
+mod measure {
+    use std::fmt::Debug;
+
+    /// The length of a string.
+    #[example(measure("abc") == 3)]
+    pub fn measure(x: &str) -> usize {
+        x.len()
+    }
+    pub fn measure<T: Debug>(x: &[T]) -> usize {
+        format!("{:?}", x).len()
+    }
+    pub fn measure(x: usize, #[default(1)] scale: usize) -> usize {
+        x * scale
+    }
+}
+
+pub struct Gauge(usize);
+
+#[overloadable::overloads]
+impl Gauge {
+    const UNIT: usize = 2;
+
+    fn read(&self) -> usize {
+        self.0 * Self::UNIT
+    }
+    fn read(self, extra: usize) -> usize {
+        self.0 + extra
+    }
+}
+
+#[test]
+fn attribute_overloads() {
+    assert_eq!(measure::measure("abc"), 3);
+    assert_eq!(measure::measure(&[1, 2][..]), 6);
+    assert_eq!(measure::measure(4), 4);
+    assert_eq!(measure::measure(4, 3), 12);
+    let gauge = &Gauge(3);
+    assert_eq!(gauge.read(), 6);
+    assert_eq!(Gauge(3).read(1), 4);
+}
+
+pub struct Sizes(u8);
+
+overloadable::overloadable_member! {
+    Sizes::take as
+    #[maybe_unsized]
+    fn(self) -> u8 {
+        self.0
+    },
+    #[sized]
+    fn(self: Box<Self>, x: u8) -> u8 {
+        self.0 + x
+    },
+    fn(&self, x: u8, y: u8) -> u8 {
+        self.0 + x + y
+    }
+}
+
+#[test]
+fn sized_annotations() {
+    assert_eq!(Sizes(1).take(), 1);
+    assert_eq!(Box::new(Sizes(1)).take(2), 3);
+    let sizes = &Sizes(1);
+    assert_eq!(sizes.take(2, 3), 6);
+}
+
+overloadable::overloadable! {
+    coerced as
+    #[coercible_to(fn(u8) -> u8)]
+    #[coercible_to(fn(u8) -> <u8 as std::ops::Add>::Output)]
+    fn(x: u8) -> u8 {
+        x + 1
+    },
+    #[coercible_to(for<'a> fn(&'a str, usize) -> usize)]
+    fn(x: &str, #[default(0)] start: usize) -> usize {
+        x.len() - start
+    }
+}
+
+#[test]
+fn coercible_overloads() {
+    assert_eq!(coerced.as_fn_ptr_u8()(1), 2);
+    assert_eq!(coerced("abc", 1), 2);
+    assert_eq!(coerced("abc"), 3);
+}
+
+overloadable::overloadable! {
+    tally as
+    fn<repeat N in 1..=4>(xs: [u32; N]) -> (usize, u32) {
+        (N, xs.iter().sum())
+    },
+    fn<repeat N in 0..2, T: Into<u32>>(first: T, rest: [T; N], tag: &str) -> String {
+        let rest: Vec<u32> = Vec::from(rest).into_iter().map(Into::into).collect();
+        format!("{}: {} {:?}", tag, first.into(), rest)
+    }
+}
+
+#[test]
+fn repeated_arities() {
+    assert_eq!(tally(1), (1, 1));
+    assert_eq!(tally(1, 2), (2, 3));
+    assert_eq!(tally(1, 2, 3), (3, 6));
+    assert_eq!(tally(1, 2, 3, 4), (4, 10));
+    assert_eq!(tally(1u8, "a"), "a: 1 []");
+    assert_eq!(tally(1u8, 2u8, "b"), "b: 1 [2]");
+}
+
+overloadable::overloadable! {
+    check as
+    fn(x: u8) -> u8 {
+        x
+    },
+    fn(message: &'static str) -> ! {
+        panic!("{}", message)
+    }
+}
+
+pub struct Checker;
+
+overloadable::overloadable_member! {
+    Checker::check as
+    fn(self) -> u8 {
+        1
+    },
+    fn(&self, message: &'static str) -> ! {
+        panic!("{}", message)
+    }
+}
+
+#[test]
+fn diverging_overloads() {
+    assert_eq!(check(1), 1);
+    assert_eq!(Checker.check(), 1);
+    assert!(std::panic::catch_unwind(|| check("stop")).is_err());
+    let checker = &Checker;
+    assert!(std::panic::catch_unwind(|| checker.check("stop")).is_err());
+    // A diverging overload can still be passed around as a function pointer.
+    let _: fn(&'static str) -> ! = check.as_fn_ptr_ref_static_str();
+}
+
+#[deny(unused_variables)]
+mod annotated_params {
+    overloadable::overloadable! {
+        pub first as
+        fn(x: u8, #[allow(unused_variables)] y: u8) -> u8 {
+            x
+        },
+        fn(x: &str, #[allow(unused_variables)] #[default(0)] y: u8) -> usize {
+            x.len()
+        }
+    }
+
+    pub struct Annotated;
+
+    overloadable::overloadable_member! {
+        pub Annotated::first as
+        fn(&self, x: u8, #[allow(unused_variables)] y: u8) -> u8 {
+            x
+        }
+    }
+}
+
+#[test]
+fn annotated_unused_params() {
+    use annotated_params::*;
+    assert_eq!(first(1, 2), 1);
+    assert_eq!(first("ab"), 2);
+    assert_eq!(first("ab", 1), 2);
+    let annotated = &Annotated;
+    assert_eq!(annotated.first(1, 2), 1);
+}
+
+overloadable::overloadable! {
+    #[signatures]
+    pub describe_plugin as
+    fn(id: u32) -> String {
+        id.to_string()
+    },
+    fn<'a>(name: &'a str, #[default(1)] version: u8) -> &'a str {
+        name
+    },
+    fn<T: Debug>((a, b): (T, T)) -> String {
+        format!("{:?} {:?}", a, b)
+    }
+}
+
+#[test]
+fn listed_signatures() {
+    assert_eq!(
+        describe_plugin::SIGNATURES,
+        [
+            "describe_plugin(id: u32) -> String",
+            "describe_plugin<'a>(name: &'a str, version: u8) -> &'a str",
+            "describe_plugin<'a>(name: &'a str) -> &'a str",
+            "describe_plugin<T: Debug>((a, b): (T, T)) -> String",
+        ],
+    );
+    assert_eq!(describe_plugin(1), "1");
+}
+
+pub struct PinnedCounter {
+    count: u32,
+    _pinned: std::marker::PhantomPinned,
+}
+
+overloadable::overloadable_member! {
+    PinnedCounter::bump as
+    fn(self: std::pin::Pin<&mut Self>) -> u32 {
+        // Safe, since `count` is never structurally pinned.
+        let this = unsafe { self.get_unchecked_mut() };
+        this.count += 1;
+        this.count
+    },
+    fn(&self) -> u32 {
+        self.count
+    }
+}
+
+#[test]
+fn pinned_receivers() {
+    let mut counter = Box::pin(PinnedCounter { count: 0, _pinned: std::marker::PhantomPinned });
+    assert_eq!(counter.as_mut().bump(), 1);
+    assert_eq!(counter.as_mut().bump(), 2);
+    let counter: &PinnedCounter = &counter;
+    assert_eq!(counter.bump(), 2);
+}
+
+mod renamed {
+    pub mod sum {
+        pub fn sum(xs: &[u32]) -> u32 {
+            xs.iter().sum()
+        }
+    }
+
+    overloadable::overloadable! {
+        #[struct_name = "SumFn"]
+        #[signatures]
+        pub sum as
+        fn(x: u32, y: u32) -> u32 {
+            x + y
+        },
+        fn(x: u32, y: u32, z: u32) -> u32 {
+            x + y + z
+        }
+    }
+}
+
+#[test]
+fn renamed_struct() {
+    assert_eq!(renamed::sum(1, 2), 3);
+    assert_eq!(renamed::sum(1, 2, 3), 6);
+    assert_eq!(renamed::sum::sum(&[1, 2, 3, 4]), 10);
+    let sum: renamed::SumFn = renamed::sum;
+    assert_eq!(sum(4, 5), 9);
+    assert_eq!(renamed::SumFn::SIGNATURES, ["sum(x: u32, y: u32) -> u32", "sum(x: u32, y: u32, z: u32) -> u32"]);
+}
+
+#[deny(non_camel_case_types)]
+mod cased {
+    overloadable::overloadable! {
+        #[struct_name]
+        pub area_of as
+        fn(side: u32) -> u32 => side * side,
+        fn(width: u32, height: u32) -> u32 => width * height,
+    }
+}
+
+#[test]
+fn camel_cased_struct() {
+    assert_eq!(cased::area_of(3), 9);
+    let area: cased::AreaOf = cased::area_of;
+    assert_eq!(area(2, 3), 6);
+    assert_eq!(cased::AreaOf::new()(4), 16);
+}
+
+overloadable::overloadable! {
+    shorter as
+    fn(a: &'a str, b: &'b str) -> &'b str where 'a: 'b {
+        if a.len() < b.len() { a } else { b }
+    },
+    fn<T, U>(items: T) -> Option<U> where T: Iterator<Item = U>, U: Ord {
+        items.min()
+    }
+}
+
+pub struct Lengths;
+
+overloadable::overloadable_member! {
+    Lengths::shortest as
+    fn<'s, T, U>(&'s self, items: T) -> Option<U> where T: Iterator<Item = U>, U: Ord + 's {
+        items.min()
+    }
+}
+
+#[test]
+fn where_clause_generics() {
+    let long = String::from("longer");
+    assert_eq!(shorter(&long, "short"), "short");
+    assert_eq!(shorter(vec![3, 1, 2].into_iter()), Some(1));
+    assert_eq!(Lengths.shortest(vec!["b", "a"].into_iter()), Some("a"));
+}
+
+#[no_implicit_prelude]
+mod no_prelude {
+    // Shadows the prelude's names, which the generated impls mustn't pick up.
+    #[allow(dead_code)]
+    pub trait Fn {}
+    #[allow(dead_code)]
+    pub trait Sized {}
+
+    ::overloadable::overloadable! {
+        #[signatures]
+        documented dyn pub scaled as
+        fn(x: u8) -> u8 {
+            x * 2
+        },
+        fn<T: ::std::clone::Clone>(x: &T) -> T {
+            x.clone()
+        },
+        fn(x: u8, y: u8) -> ! {
+            ::std::panic!("{} {}", x, y)
+        },
+        async fn(x: u16) -> u16 {
+            x
+        },
+        #[boxed]
+        fn(x: u32) -> impl ::std::ops::Fn(u32) -> u32 {
+            move |y| x + y
+        }
+    }
+
+    ::overloadable::overloadable_stable! {
+        pub stable_scaled as
+        fn(x: u8) -> u8 {
+            x * 3
+        }
+    }
+
+    pub struct Scaler;
+
+    ::overloadable::overloadable_member! {
+        pub Scaler::scale as
+        fn(&self, x: u8) -> u8 {
+            x * 4
+        },
+        fn(self, x: u8) -> u8 {
+            x * 5
+        }
+    }
+}
+
+#[test]
+fn without_implicit_prelude() {
+    use no_prelude::*;
+    assert_eq!(no_prelude::scaled(2u8), 4);
+    assert_eq!(no_prelude::scaled(&String::from("a")), "a");
+    assert_eq!(no_prelude::scaled(2u32)(3), 5);
+    assert_eq!(no_prelude::stable_scaled.call((2,)), 6);
+    let scaler = &no_prelude::Scaler;
+    assert_eq!(scaler.scale(2), 8);
+    assert_eq!(no_prelude::Scaler.scale(2), 10);
+}
+
+pub struct Wrapped {
+    value: u8,
+}
+
+overloadable::overloadable_member! {
+    Wrapped::peek as
+    fn<P>(self: std::pin::Pin<P>) -> u8 where P: std::ops::Deref<Target = Self> {
+        self.value
+    },
+    fn(&self, offset_by: u8) -> u8 {
+        self.value + offset_by
+    }
+}
+
+#[test]
+fn generic_receivers() {
+    let wrapped = Wrapped { value: 3 };
+    assert_eq!(std::pin::Pin::new(&wrapped).peek(), 3);
+    assert_eq!(std::pin::Pin::new(Box::new(Wrapped { value: 4 })).peek(), 4);
+    assert_eq!(wrapped.peek(1), 4);
+}
+
+overloadable::overloadable! {
+    parse_sum as
+    fn(x: &str) -> Result<u32, std::num::ParseIntError> {
+        Ok(x.trim().parse::<u32>()? * 10)
+    },
+    fn(x: &str, y: &str) -> Result<u32, Box<dyn std::error::Error>> {
+        let sum = x.parse::<u32>()?.checked_add(y.parse::<u32>()?).ok_or("overflow")?;
+        Ok(sum)
+    },
+    fn<T: std::str::FromStr>(x: &[&str], _: std::marker::PhantomData<T>) -> Option<Vec<T>> {
+        let mut parsed = Vec::new();
+        for x in x {
+            parsed.push(x.parse().ok()?);
+        }
+        Some(parsed)
+    }
+}
+
+#[test]
+fn fallible_overloads() {
+    assert_eq!(parse_sum(" 4 "), Ok(40));
+    assert!(parse_sum("four").is_err());
+    assert_eq!(parse_sum("1", "2").unwrap(), 3);
+    assert_eq!(parse_sum("4294967295", "1").unwrap_err().to_string(), "overflow");
+    assert_eq!(parse_sum(&["1", "2"][..], std::marker::PhantomData::<u8>), Some(vec![1, 2]));
+    assert_eq!(parse_sum(&["1", "x"][..], std::marker::PhantomData::<u8>), None);
+}
+
+overloadable::overloadable! {
+    next_up as
+    fn(x: u8) -> u8 => x + 1,
+    fn(x: u16) -> u16 {
+        x + 2
+    },
+    fn<T>(x: Vec<T>) -> usize where T: Clone => x.len(),
+    fn(x: i8, #[default(1)] y: i8) -> i8 => x + y,
+    fn(x: &str) -> String => match x {
+        "a" => String::from("b"),
+        x => format!("{}+", x),
+    }
+}
+
+pub struct Stepper;
+
+overloadable::overloadable_member! {
+    Stepper::step as
+    fn(&self, x: u8) -> u8 => x + 3,
+    fn(self) -> u8 {
+        0
+    }
+}
+
+#[test]
+fn expression_bodies() {
+    assert_eq!(next_up(1u8), 2);
+    assert_eq!(next_up(1u16), 3);
+    assert_eq!(next_up(vec![1, 2]), 2);
+    assert_eq!(next_up(1i8), 2);
+    assert_eq!(next_up(1i8, 3), 4);
+    assert_eq!(next_up("a"), "b");
+    assert_eq!(next_up("c"), "c+");
+    let stepper = &Stepper;
+    assert_eq!(stepper.step(1), 4);
+    assert_eq!(Stepper.step(), 0);
+}
+
+#[derive(Default)]
+pub struct Inner {
+    hits: u32,
+}
+
+#[derive(Default)]
+pub struct Holder {
+    inner: Inner,
+}
+
+overloadable::overloadable_member! {
+    Holder::inner as
+    fn(&mut self) -> &mut Inner {
+        &mut self.inner
+    },
+    fn(self, hits: u32) -> Inner {
+        Inner { hits: self.inner.hits + hits }
+    }
+}
+
+#[test]
+fn mutable_field_accessors() {
+    let mut holder = Holder::default();
+    let borrowed = &mut holder;
+    borrowed.inner().hits += 2;
+    let inner = borrowed.inner();
+    inner.hits *= 3;
+    assert_eq!(holder.inner.hits, 6);
+    assert_eq!(holder.inner(1).hits, 7);
+}
+
+pub struct Twice;
+
+overloadable::overloadable_member! {
+    Twice::first as
+    fn(&self) -> u8 => 1,
+    fn(self, x: u8) -> u8 => x
+}
+
+overloadable::overloadable_member! {
+    Twice::second as
+    fn(&self) -> u8 => 2,
+    fn(self, x: u8) -> u8 => x * 2
+}
+
+#[test]
+fn separate_member_invocations() {
+    let twice = &Twice;
+    assert_eq!(twice.first(), 1);
+    assert_eq!(twice.second(), 2);
+    assert_eq!(Twice.first(3), 3);
+    assert_eq!(Twice.second(3), 6);
+}
+
+overloadable::overloadable! {
+    hinted as
+    #[cfg_attr(debug_assertions, inline(always))]
+    fn(x: u8) -> u8 => x + 1,
+    #[cfg_attr(not(debug_assertions), cold, allow(unused_parens))]
+    fn(x: u16) -> u16 => x + 2
+}
+
+#[test]
+fn conditional_hints() {
+    assert_eq!(hinted(1u8), 2);
+    assert_eq!(hinted(1u16), 3);
+}
+
+pub struct Offsetter {
+    by: i32,
+}
+
+overloadable::overloadable_for! {
+    pub Offsetter as
+    fn(x: i32) -> i32 => x + self.by,
+    fn(x: &str) -> String {
+        format!("{}{:+}", x, self.by)
+    },
+    fn<T: Into<i64>>(x: T, _: std::marker::PhantomData<T>) -> i64 => x.into() + i64::from(self.by)
+}
+
+#[test]
+fn overloads_for_existing_types() {
+    let offsetter = Offsetter { by: 3 };
+    assert_eq!(offsetter(2), 5);
+    assert_eq!(offsetter("x"), "x+3");
+    assert_eq!(offsetter.with::<(u8, _)>()(1, std::marker::PhantomData), 4);
+    let negative = Offsetter { by: -1 };
+    assert_eq!([1, 2].iter().map(|&x| negative(x)).collect::<Vec<_>>(), [0, 1]);
+}
+
+pub struct Repeated<T>(T);
+
+overloadable::overloadable_for! {
+    Repeated<usize> as
+    fn(x: &str) -> String => x.repeat(self.0),
+    fn<T: Clone>(x: T, _: std::marker::PhantomData<T>) -> Vec<T> => vec![x; self.0]
+}
+
+#[test]
+fn overloads_for_concrete_generic_types() {
+    let twice = Repeated(2);
+    assert_eq!(twice("ab"), "abab");
+    assert_eq!(twice.with::<(u8, _)>()(1, std::marker::PhantomData), [1, 1]);
+}
+
+overloadable::overloadable! {
+    prefixed { prefix: String, seen: std::cell::Cell<u32> } as
+    fn(x: &str) -> String {
+        self.seen.set(self.seen.get() + 1);
+        format!("{}{}", self.prefix, x)
+    },
+    fn(x: u8) -> usize {
+        self.seen.set(self.seen.get() + 1);
+        self.prefix.len() + usize::from(x)
+    }
+}
+
+fn call_once<F: FnOnce(&str) -> String>(f: F) -> String {
+    f("once")
+}
+
+fn call_mut<F: FnMut(u8) -> usize>(mut f: F) -> usize {
+    f(1) + f(2)
+}
+
+#[test]
+fn stateful_forwarding() {
+    let new = || prefixed { prefix: String::from("pre-"), seen: std::cell::Cell::new(0) };
+    assert_eq!(call_once(new()), "pre-once");
+    let mut counted = new();
+    assert_eq!(call_mut(&mut counted), 11);
+    assert_eq!(call_mut(&counted), 11);
+    assert_eq!(counted.seen.get(), 4);
+    let boxed: Box<dyn FnOnce(&str) -> String> = Box::new(new());
+    assert_eq!(boxed("boxed"), "pre-boxed");
+}
+
+overloadable::overloadable! {
+    #[repr(transparent)]
+    #[derive(Clone, Copy)]
+    pub wrapping_scale { factor: u32 } as
+    fn(x: u32) -> u32 => x.wrapping_mul(self.factor),
+    fn(x: u64) -> u64 => x.wrapping_mul(u64::from(self.factor))
+}
+
+overloadable::overloadable! {
+    #[repr(C)]
+    pub c_layout as
+    fn(x: u8) -> u8 => x
+}
+
+#[test]
+fn repr_attributes() {
+    let scale = wrapping_scale::new(3);
+    assert_eq!(scale(2u32), 6);
+    assert_eq!(scale(2u64), 6);
+    assert_eq!(std::mem::size_of::<wrapping_scale>(), std::mem::size_of::<u32>());
+    // Transparent, so it can be reinterpreted as its field.
+    let factor: u32 = unsafe { std::mem::transmute(scale) };
+    assert_eq!(factor, 3);
+    assert_eq!(c_layout(1), 1);
+}
+
+overloadable::overloadable! {
+    widened as
+    #[coerce_via(Into)]
+    fn(x: u64) -> u64 => x * 2,
+    fn(x: u64, label: &str) -> String => format!("{}: {}", label, x),
+    #[coerce_via(Into)]
+    fn(x: f64, y: f64, z: f64) -> f64 {
+        x + y + z
+    }
+}
+
+#[test]
+fn coerced_via_into() {
+    assert_eq!(widened(3u8), 6);
+    assert_eq!(widened(3u32), 6);
+    assert_eq!(widened(3u64), 6);
+    assert_eq!(widened(3u64, "three"), "three: 3");
+    assert_eq!(widened(1u8, 2.5f32, 0.5f64), 4.0);
+}
+
+// The examples below run as `__exemplified_example_{0,1,2}`.
+overloadable::overloadable! {
+    exemplified as
+    #[example(exemplified(1u8) == 2)]
+    #[example(exemplified(0u8) == 0)]
+    fn(x: u8) -> u8 => x * 2,
+    #[example(exemplified("ab") == "abab")]
+    fn(x: &str) -> String => x.repeat(2)
+}
+
+#[test]
+#[allow(unnameable_test_items)]
+fn block_examples() {
+    // Examples of a set declared in a block aren't run by the harness, but can be
+    // called like any other function.
+    overloadable::overloadable! {
+        local_exemplified as
+        #[example(local_exemplified(1u8) == 2)]
+        fn(x: u8) -> u8 => x + 1
+    }
+    __local_exemplified_example_0();
+}
+
+#[test]
+#[should_panic(expected = "doesn't hold")]
+#[allow(unnameable_test_items)]
+fn wrong_examples() {
+    overloadable::overloadable! {
+        misexemplified as
+        #[example(misexemplified(2u8) == 5)]
+        fn(x: u8) -> u8 => x * 2
+    }
+    __misexemplified_example_0();
+}
+
+
+overloadable::overloadable! {
+    iterate as
+    fn<I: IntoIterator>(x: I) -> I::IntoIter {
+        x.into_iter()
+    },
+    fn<I>(x: I, skip: usize) -> std::iter::Skip<<I as IntoIterator>::IntoIter> where I: IntoIterator {
+        x.into_iter().skip(skip)
+    }
+}
+
+#[test]
+fn associated_type_returns() {
+    assert_eq!(iterate(vec![1u8, 2, 3]).collect::<Vec<_>>(), [1, 2, 3]);
+    assert_eq!(iterate(Some('a')).next(), Some('a'));
+    assert_eq!(iterate(1u16..4, 1).sum::<u16>(), 5);
+}
+
+overloadable::overloadable! {
+    retired as
+    #[deprecated(note = "Pass an `i64` instead.")]
+    fn(x: i32) -> i64 {
+        x as i64
+    },
+    #[deprecated]
+    fn<T: Into<i64>>(x: T, y: T) -> i64 {
+        x.into() + y.into()
+    },
+}
+
+#[test]
+fn deprecated_overloads() {
+    assert_eq!(retired(2i32), 2);
+    assert_eq!(retired(2u8, 3u8), 5);
+    #[allow(deprecated)]
+    let shim = retired.as_fn_ptr_i32();
+    assert_eq!(shim(3), 3);
+    #[allow(deprecated)]
+    let result = retired.call_i32(4);
+    assert_eq!(result, 4);
+    #[allow(deprecated)]
+    let result = retired.call_t_t(4u8, 1);
+    assert_eq!(result, 5);
+}
+
+pub struct Scaler(usize);
+
+overloadable::overloadable_member! {
+    inherent Scaler::scale as
+    fn(x: usize) -> usize {
+        x * 2
+    },
+    fn(&self, x: usize) -> usize {
+        self.0 * x
+    }
+}
+
+pub struct Shifted<T>(T);
+
+overloadable::overloadable_member! {
+    grouped inherent Shifted<T>::shift where T: Copy + std::ops::Add<Output = T> as
+    fn(x: T, y: T) -> T {
+        x + y
+    },
+    fn(&self, x: T) -> T {
+        self.0 + x
+    }
+}
+
+#[test]
+fn associated_and_method_overloads() {
+    assert_eq!(Scaler::scale(4), 8);
+    assert_eq!(Scaler(3).scale(4), 12);
+    assert_eq!(Scaler::scale(Scaler::scale(1)), 4);
+    assert_eq!(Shifted::<u8>::shift(1, 2), 3);
+    assert_eq!(Shifted(3u8).shift(4), 7);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pixel(u8, u8);
+
+overloadable::overloadable_member! {
+    inherent Pixel::origin as
+    fn() -> Self => Pixel(0, 0),
+    fn(self, dx: u8) -> Self => Pixel(self.0 - dx, self.1),
+}
+
+#[test]
+fn self_constructors() {
+    let origin: Pixel = Pixel::origin();
+    assert_eq!(origin, Pixel(0, 0));
+    assert_eq!(Pixel(3, 4).origin(1), Pixel(2, 4));
+}
+
+// Without `inherent`, only traits are declared, which works for foreign types too.
+overloadable::overloadable_member! {
+    String::describe_text as
+    fn as TextDescription() -> u8 {
+        1
+    },
+    fn(&self) -> usize {
+        self.len()
+    }
+}
+
+#[test]
+fn foreign_member_overloads() {
+    assert_eq!(<String as TextDescription>::describe_text(), 1);
+    assert_eq!(String::from("ab").describe_text(), 2);
+}
+
+overloadable::overloadable! {
+    pub extended as
+    #[example(extended(2u8) == 4)]
+    fn(x: u8) -> u8 => x * 2,
+    fn(x: &str) -> usize => x.len(),
+}
+
+mod extensions {
+    use super::extended;
+
+    overloadable::overloadable_extend! {
+        extended as
+        #[example(extended(1u8, 2u8) == 3)]
+        fn(x: u8, y: u8) -> u8 => x + y,
+        fn<T: Clone>(x: &[T]) -> Vec<T> => x.to_vec(),
+    }
+}
+
+#[test]
+fn extended_overloads() {
+    assert_eq!(extended(3u8), 6);
+    assert_eq!(extended("abc"), 3);
+    assert_eq!(extended(3u8, 4u8), 7);
+    assert_eq!(extended(&[1, 2][..]), [1, 2]);
+}
+
+pub(crate) struct Celsius(pub(crate) f32);
+
+overloadable::overloadable! {
+    pub(crate) to_celsius as
+    fn(fahrenheit: f32) -> Celsius => Celsius((fahrenheit - 32.0) * 5.0 / 9.0),
+}
+
+overloadable::overloadable! {
+    pub widen as
+    fn(x: u8) -> u16 => x as u16 * 2,
+    fn(x: u16) -> u32 => x as u32 * 2,
+}
+
+fn apply<F: Fn(u8) -> u16>(f: F) -> u16 {
+    f(200)
+}
+
+fn apply_output<F: FnOnce<(u16,)>>(f: F) -> F::Output {
+    f(300)
+}
+
+#[test]
+fn public_outputs() {
+    assert_eq!(to_celsius(212.0).0, 100.0);
+    assert_eq!(apply(widen), 400);
+    assert_eq!(apply_output(widen), 600u32);
+}
+
+overloadable::overloadable! {
+    unwrap_or_zero as
+    #[dispatch]
+    fn(Some(x): Option<u8>) -> u8 => x,
+    #[dispatch]
+    fn(None: Option<u8>) -> u8 => 0,
+    #[dispatch]
+    fn(Some(x): Option<u8>, Some(y): Option<u8>) -> u8 => x + y,
+    #[dispatch]
+    fn(_: Option<u8>, _: Option<u8>) -> u8 => 0,
+    fn(x: &str) -> u8 => x.parse().unwrap_or(0),
+}
+
+#[test]
+fn dispatched_patterns() {
+    assert_eq!(unwrap_or_zero(Some(3u8)), 3);
+    assert_eq!(unwrap_or_zero(None::<u8>), 0);
+    assert_eq!(unwrap_or_zero(Some(3u8), Some(4u8)), 7);
+    assert_eq!(unwrap_or_zero(Some(3u8), None), 0);
+    assert_eq!(unwrap_or_zero("5"), 5);
+}
+
+overloadable::overloadable! {
+    pick_width as
+    fn(_: u8) -> &'static str => "u8",
+    #[overload_priority(2)]
+    fn(_: u64) -> &'static str => "u64",
+    #[overload_priority(1)]
+    fn(_: u32) -> &'static str => "u32",
+    fn(_: &str, _: u8) -> &'static str => "str",
+}
+
+#[test]
+fn prioritized_overloads() {
+    // An unsuffixed literal could be given to any of the first three overloads.
+    assert_eq!(pick_width!(1), "u64");
+    assert_eq!(pick_width!(1u32), "u32");
+    assert_eq!(pick_width!(1u8), "u8");
+    assert_eq!(pick_width!("a", 1), "str");
+    assert_eq!(pick_width(1u8), "u8");
+}
+
+overloadable::overloadable! {
+    byte_len as
+    fn(x: &[u8]) -> usize => x.len(),
+    fn(x: &str) -> usize => x.len() * 10,
+    fn(x: &[u8], y: &mut Vec<u8>) -> usize {
+        y.extend_from_slice(x);
+        y.len()
+    },
+}
+
+overloadable::overloadable! {
+    first_half as
+    fn(x: &[u8]) -> &[u8] => &x[..x.len() / 2],
+    fn(x: &str) -> &str => &x[..x.len() / 2],
+    fn<'a>(x: &'a str, rest: usize) -> std::borrow::Cow<'_, str> => x[rest..].into(),
+}
+
+#[test]
+fn elided_slice_lifetimes() {
+    let bytes = [1u8, 2, 3];
+    assert_eq!(byte_len(&bytes[..]), 3);
+    assert_eq!(byte_len("ab"), 20);
+    let mut buffer = vec![0];
+    assert_eq!(byte_len(&bytes[..], &mut buffer), 4);
+    assert_eq!(first_half(&[1u8, 2, 3, 4][..]), [1, 2]);
+    assert_eq!(first_half("abcd"), "ab");
+    assert_eq!(first_half("abcd", 1usize), "bcd");
+    // The lifetimes are late-bound, so the accessors are higher-ranked.
+    let halving: for<'a> fn(&'a [u8]) -> &'a [u8] = first_half.as_fn_ptr_ref_u8();
+    assert_eq!(halving(&[1, 2][..]), [1]);
+    assert_eq!(first_half.boxed_ref_str()("abcd"), "ab");
+    assert_eq!(first_half.as_fn_ptr_ref_str_usize()("abc", 2), "c");
+}
+
+overloadable::overloadable! {
+    #[instance_const]
+    double_it as
+    fn(x: u8) -> u8 => x * 2,
+    fn(x: &str) -> String => x.repeat(2),
+}
+
+overloadable::overloadable! {
+    #[instance_const = "TRIPLE"]
+    #[struct_name = "TripleFn"]
+    triple_it as
+    fn(x: u8) -> u8 => x * 3,
+}
+
+static SCALERS: [&(dyn Fn(u8) -> u8 + Sync); 2] = [&DOUBLE_IT, &TRIPLE];
+
+#[test]
+fn instance_consts() {
+    assert_eq!(SCALERS.iter().map(|f| f(2)).collect::<Vec<_>>(), [4, 6]);
+    assert_eq!(DOUBLE_IT("ab"), "abab");
+    const ALSO_DOUBLE: double_it = DOUBLE_IT;
+    assert_eq!(ALSO_DOUBLE(1), 2);
+}
+
+pub struct Offset(i32);
+
+impl std::ops::Add<Offset> for i32 {
+    type Output = i64;
+
+    fn add(self, rhs: Offset) -> i64 {
+        i64::from(self) + i64::from(rhs.0)
+    }
+}
+
+overloadable::overloadable! {
+    add_across as
+    fn<A, B>(a: A, b: B) -> B::Output where A: Into<Offset>, B: std::ops::Add<Offset> {
+        b + a.into()
+    },
+    fn<A, B, C>(a: A, b: B, c: C) -> <B::Output as std::ops::Add<C>>::Output
+    where
+        B: std::ops::Add<A>,
+        B::Output: std::ops::Add<C>,
+    {
+        b + a + c
+    },
+}
+
+impl From<u8> for Offset {
+    fn from(x: u8) -> Self {
+        Offset(i32::from(x))
+    }
+}
+
+#[test]
+fn interdependent_bounds() {
+    let sum: i64 = add_across(2u8, 3i32);
+    assert_eq!(sum, 5);
+    assert_eq!(add_across(1u8, 2u8, 3u8), 6);
+    assert_eq!(add_across(1.5f32, 2.0f32, 0.5f32), 4.0);
+}
+
+pub struct Endpoints<T>(T, T);
+
+overloadable::overloadable_member! {
+    Endpoints<T>::combine where T: Clone + std::ops::Add<Output = T> as
+    fn(&self) -> T {
+        self.0.clone() + self.1.clone()
+    },
+    fn<U: Into<T>>(&mut self, extra: U) -> T {
+        self.1 = self.1.clone() + extra.into();
+        self.1.clone()
+    },
+    fn(self: Box<Self>) -> (T, T) where T: Default {
+        (self.0 + T::default(), self.1)
+    },
+}
+
+#[test]
+fn header_generics() {
+    let endpoints = &Endpoints(1u32, 2);
+    assert_eq!(endpoints.combine(), 3);
+    let endpoints = &mut Endpoints(1u32, 2);
+    assert_eq!(endpoints.combine(4u8), 6);
+    assert_eq!(Box::new(Endpoints(5u64, 6)).combine(), (5, 6));
+}
+
+overloadable::overloadable! {
+    platform_bits as
+    #[cfg(target_pointer_width = "64")]
+    fn(x: u8) -> u32 => u32::from(x) * 64,
+    #[cfg(not(target_pointer_width = "64"))]
+    fn(y: u8) -> u32 => u32::from(y) * 32,
+    fn(x: &str) -> usize => x.len(),
+}
+
+#[test]
+fn cfg_alternative_bodies() {
+    let expected = if cfg!(target_pointer_width = "64") { 128 } else { 64 };
+    assert_eq!(platform_bits(2u8), expected);
+    assert_eq!(platform_bits.as_fn_ptr_u8()(1), expected / 2);
+    assert_eq!(platform_bits("ab"), 2);
+}
+
+overloadable::overloadable! {
+    erased as
+    fn(x: u8) -> u8 => x + 1,
+    fn(x: char, y: usize) -> String => x.to_string().repeat(y),
+    fn<T: Clone>(x: &T) -> T => x.clone(),
+}
+
+#[test]
+fn boxed_overloads() {
+    let increments: Vec<Box<dyn Fn(u8) -> u8>> = vec![erased.boxed_u8(), Box::new(|x| x * 2)];
+    assert_eq!(increments.iter().map(|f| f(3)).collect::<Vec<_>>(), [4, 6]);
+    let mut stored: Box<dyn FnMut(char, usize) -> String> = erased.boxed_char_usize();
+    assert_eq!(stored('a', 2), "aa");
+}
@@ -0,0 +1,32 @@
+//! Checks that overloads compile in a `#![no_std]` crate, with only `alloc`. As a
+//! member of the workspace, it is built with the `std` feature of `overloadable`
+//! enabled by the other members, as it would be by any other crate in its graph.
+#![no_std]
+#![feature(unboxed_closures, fn_traits)]
+extern crate alloc;
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::future::Future;
+use core::pin::Pin;
+
+overloadable::overloadable! {
+    pub describe as
+    fn(x: u8) -> u8 {
+        x * 2
+    },
+    fn(x: &str) -> String {
+        String::from(x)
+    },
+    #[boxed]
+    fn(x: u16) -> impl Fn(u16) -> u16 {
+        move |y| x + y
+    },
+    async fn(x: u32) -> u32 {
+        x + 1
+    }
+}
+
+pub fn use_describe() -> (u8, String, u16, Pin<Box<dyn Future<Output = u32>>>) {
+    (describe(2u8), describe("two"), describe(1u16)(2), describe(3u32))
+}
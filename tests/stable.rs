@@ -0,0 +1,201 @@
+//! Uses no unstable features, so that this works on stable.
+use std::fmt::Debug;
+
+overloadable::overloadable_stable! {
+    pub(crate) func_name as
+    fn(x: usize) -> usize {
+        x * 2
+    },
+    fn<T>((x, y): (T, usize)) -> String where T: Debug {
+        format!("{:?}, {:?}", x, y)
+    },
+    fn() -> &'static str {
+        "none"
+    },
+    fn(a: &str, b: &str) -> usize {
+        a.len() + b.len()
+    }
+}
+
+#[test]
+fn stable_overloads() {
+    assert_eq!(func_name.call((2,)), 4);
+    assert_eq!(func_name.call(((1, 2),)), "1, 2");
+    assert_eq!(func_name.call(()), "none");
+    assert_eq!(func_name.call(("ab", "c")), 3);
+}
+
+overloadable::overloadable_stable! {
+    caller_line as
+    #[track_caller]
+    fn() -> u32 {
+        std::panic::Location::caller().line()
+    }
+}
+
+#[test]
+fn track_caller_stable() {
+    assert_eq!(caller_line.call(()), line!());
+}
+
+#[test]
+fn call_macro() {
+    use overloadable::call;
+    assert_eq!(call!(func_name), "none");
+    assert_eq!(call!(func_name, "ab", "c",), 3);
+}
+
+overloadable::overloadable_stable! {
+    dyn stable_dispatched as
+    fn(x: u8) -> u8 {
+        x
+    },
+    fn(x: u8, y: u8) -> u8 {
+        x + y
+    }
+}
+
+#[test]
+fn stable_dyn_dispatch() {
+    let boxed: Box<dyn StableDispatchedDyn> = Box::new(stable_dispatched);
+    assert_eq!(boxed.call_u8(1), 1);
+    assert_eq!(boxed.call_u8_u8(1, 2), 3);
+}
+
+overloadable::overloadable_stable! {
+    offset_by { offset: i64 } as
+    fn(x: i64) -> i64 {
+        x + self.offset
+    }
+}
+
+#[test]
+fn stateful_stable_overloads() {
+    assert_eq!(offset_by { offset: 2 }.call((1,)), 3);
+    assert_eq!(offset_by::new(-1).call((1,)), 0);
+}
+
+overloadable::overloadable_stable! {
+    stable_check as
+    fn(x: u8) -> u8 {
+        x
+    },
+    fn(message: &'static str, fail: bool) -> ! {
+        panic!("{} ({})", message, fail)
+    }
+}
+
+#[test]
+fn stable_diverging_overloads() {
+    assert_eq!(stable_check.call((1,)), 1);
+    let result = std::panic::catch_unwind(|| stable_check.call(("stop", true)));
+    assert!(result.is_err());
+}
+
+overloadable::overloadable_stable! {
+    stable_parse as
+    fn(x: &str) -> Result<u8, std::num::ParseIntError> {
+        Ok(x.parse::<u8>()? / 2)
+    }
+}
+
+#[test]
+fn stable_fallible_overloads() {
+    assert_eq!(stable_parse.call(("8",)), Ok(4));
+    assert!(stable_parse.call(("eight",)).is_err());
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Meters(u32);
+
+overloadable::overloadable_op! {
+    + for Meters as
+    fn(self, rhs: Meters) -> Meters {
+        Meters(self.0 + rhs.0)
+    },
+    fn(self, rhs: u32) -> Meters => Meters(self.0 + rhs)
+}
+
+overloadable::overloadable_op! {
+    == for Meters as
+    fn(&self, other: &Meters) -> bool => self.0 == other.0,
+    fn(&self, other: &u32) -> bool => self.0 == *other
+}
+
+overloadable::overloadable_op! {
+    < for Meters as
+    fn(&self, other: &Meters) -> Option<std::cmp::Ordering> => self.0.partial_cmp(&other.0)
+}
+
+overloadable::overloadable_op! {
+    += for Meters as
+    fn(&mut self, rhs: u32) {
+        self.0 += rhs;
+    }
+}
+
+overloadable::overloadable_op! {
+    - for Meters as
+    fn(self, rhs: u32) -> Meters => Meters(self.0 - rhs),
+    fn(self) -> i64 => -i64::from(self.0)
+}
+
+pub struct Wrapper<T>(T);
+
+overloadable::overloadable_op! {
+    * for Wrapper<T> as
+    fn<T>(self, rhs: usize) -> Vec<T> where T: Clone => vec![self.0; rhs]
+}
+
+#[test]
+fn operator_overloads() {
+    assert_eq!(Meters(1) + Meters(2), Meters(3));
+    assert_eq!(Meters(1) + 2, 3);
+    assert!(Meters(1) != 2);
+    assert!(Meters(1) < Meters(2));
+    let mut total_length = Meters(1);
+    total_length += 4;
+    assert_eq!(total_length, 5);
+    assert_eq!(Meters(5) - 2, 3);
+    assert_eq!(-Meters(5), -5);
+    assert_eq!(Wrapper('a') * 2, ['a', 'a']);
+}
+
+overloadable::overloadable_stable! {
+    halve as
+    fn(x: u16) -> u16 => x / 2,
+    #[overload_priority(1)]
+    fn(x: f64) -> f64 => x / 2.0,
+    #[overload_priority(1)]
+    fn(x: i64) -> i64 => x / 2,
+}
+
+#[test]
+fn prioritized_overloads() {
+    assert_eq!(halve!(7), 3i64);
+    assert_eq!(halve!(7.0), 3.5);
+    assert_eq!(halve!(7u16), 3u16);
+    assert_eq!(halve.call((7u16,)), 3);
+}
+
+overloadable::overloadable_stable! {
+    /// Counts the items given.
+    #[derive(Clone, Copy)]
+    pub count_items as
+    fn(x: &[u8]) -> usize => x.len(),
+    fn(x: &str) -> usize => x.chars().count(),
+}
+
+#[test]
+fn leading_doc_comments() {
+    let counter = count_items;
+    assert_eq!(counter.call((&[1u8, 2][..],)), 2);
+    assert_eq!(count_items.call(("abc",)), 3);
+}
+
+#[test]
+fn boxed_overloads() {
+    let halves: [Box<dyn Fn(u16) -> u16>; 2] = [halve.boxed_u16(), Box::new(|x| x / 4)];
+    assert_eq!(halves.iter().map(|f| f(8)).collect::<Vec<_>>(), [4, 2]);
+    assert_eq!(count_items.boxed_ref_str()("abc"), 3);
+}
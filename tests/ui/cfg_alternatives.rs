@@ -0,0 +1,19 @@
+#![feature(unboxed_closures, fn_traits)]
+
+overloadable::overloadable! {
+    overlapping as
+    #[cfg(all())]
+    fn(x: u8) -> u8 => x,
+    #[cfg(not(any()))]
+    fn(x: u8) -> u8 => x + 1,
+}
+
+overloadable::overloadable! {
+    disabled as
+    #[cfg(any())]
+    fn(x: u8) -> u8 => x,
+    #[cfg(any())]
+    fn(x: u8) -> u8 => x + 1,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+#![feature(unboxed_closures, fn_traits)]
+
+overloadable::overloadable! {
+    func as
+    #[coerce_via(Into)]
+    fn(x: u64) -> u64 {
+        x
+    },
+    fn(x: &str) -> usize {
+        x.len()
+    }
+}
+
+fn main() {}
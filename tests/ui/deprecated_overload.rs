@@ -0,0 +1,36 @@
+#![feature(unboxed_closures, fn_traits)]
+#![deny(deprecated)]
+
+overloadable::overloadable! {
+    retired as
+    #[deprecated(note = "Pass an `i64` instead.")]
+    fn(x: i32) -> i64 {
+        x as i64
+    },
+    fn(x: i64) -> i64 {
+        x
+    }
+}
+
+struct Counter(u32);
+
+overloadable::overloadable_member! {
+    Counter::count as
+    #[deprecated(note = "Use `count(&self)` instead.")]
+    fn(self) -> u32 {
+        self.0
+    },
+    fn(&self) -> u32 {
+        self.0
+    }
+}
+
+fn main() {
+    // Calls through the `Fn*` traits can't be linted.
+    retired(1i64);
+    retired(1i32);
+    retired.call_i32(1);
+    retired.as_fn_ptr_i32();
+    let _ = (&Counter(0)).count();
+    let _ = Counter(0).count();
+}
@@ -0,0 +1,18 @@
+#![feature(unboxed_closures, fn_traits)]
+#![deny(deprecated)]
+
+overloadable::overloadable! {
+    #[warn_deref_overlap]
+    measure as
+    fn(x: &str) -> usize {
+        x.len()
+    },
+    fn(x: &String) -> usize {
+        x.capacity()
+    },
+    fn(x: &str, y: u8) -> usize {
+        x.len() + y as usize
+    }
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+#![feature(unboxed_closures, fn_traits)]
+
+overloadable::overloadable! {
+    first_only as
+    #[dispatch]
+    fn(Some(x): Option<u8>) -> u8 => x,
+    #[dispatch]
+    fn(Ok(x): Result<u8, ()>) -> u8 => x,
+    #[dispatch]
+    fn(Err(()): Result<u8, ()>) -> u8 => 0,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+#![feature(unboxed_closures, fn_traits)]
+
+overloadable::overloadable! {
+    func as
+    fn(x: usize) -> usize {
+        x
+    },
+    fn(y: usize) -> usize {
+        y * 2
+    }
+}
+
+fn main() {}
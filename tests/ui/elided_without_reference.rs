@@ -0,0 +1,12 @@
+#![feature(unboxed_closures, fn_traits)]
+
+pub struct Foo;
+
+overloadable::overloadable_member! {
+    Foo::name as
+    fn() -> &str {
+        "foo"
+    }
+}
+
+fn main() {}
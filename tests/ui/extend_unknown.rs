@@ -0,0 +1,14 @@
+#![feature(unboxed_closures, fn_traits)]
+
+overloadable::overloadable! {
+    pub area as
+    fn(side: f32) -> f32 => side * side,
+}
+
+overloadable::overloadable_extend! {
+    areas as
+    fn(radius: f64) -> f64 => radius * radius,
+    fn(width: f64, height: f64) -> f64 => width * height,
+}
+
+fn main() {}
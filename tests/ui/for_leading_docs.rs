@@ -0,0 +1,11 @@
+#![feature(unboxed_closures, fn_traits)]
+
+struct Adder;
+
+overloadable::overloadable_for! {
+    /// Adds things.
+    Adder as
+    fn(x: u8, y: u8) -> u8 => x + y,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+#![feature(unboxed_closures, fn_traits)]
+
+pub struct Wrapper<T>(T);
+
+overloadable::overloadable_for! {
+    Wrapper<T> as
+    fn<T>(x: T) -> T {
+        x
+    }
+}
+
+fn main() {}
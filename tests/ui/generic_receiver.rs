@@ -0,0 +1,10 @@
+pub struct Foo;
+
+overloadable::overloadable_member! {
+    Foo::get as
+    fn<T>(self: T) -> usize where T: AsRef<Self> {
+        0
+    }
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+#![feature(unboxed_closures, fn_traits)]
+
+overloadable::overloadable! {
+    func as
+    fn(&self, x: u8) -> u8 {
+        x
+    }
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+#![feature(unboxed_closures, fn_traits)]
+
+overloadable::overloadable! {
+    #[instance_const]
+    scaled { factor: u32 } as
+    fn(x: u32) -> u32 => x * self.factor,
+}
+
+fn main() {}
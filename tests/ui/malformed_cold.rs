@@ -0,0 +1,11 @@
+#![feature(unboxed_closures, fn_traits)]
+
+overloadable::overloadable! {
+    rarely as
+    #[cold(always)]
+    fn(x: u8) -> u8 {
+        x
+    }
+}
+
+fn main() {}
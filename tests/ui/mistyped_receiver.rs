@@ -0,0 +1,10 @@
+pub struct Foo;
+
+overloadable::overloadable_member! {
+    Foo::get as
+    fn(&mut self x: usize) -> usize {
+        x
+    }
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+#![feature(unboxed_closures, fn_traits)]
+
+overloadable::overloadable! {
+    parse as
+    #[must_use]
+    fn(x: &str) -> usize {
+        x.len()
+    }
+}
+
+fn main() {}
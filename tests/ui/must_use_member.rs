@@ -0,0 +1,21 @@
+#![deny(unused_must_use)]
+
+pub struct Parser;
+
+overloadable::overloadable_member! {
+    Parser::parse as
+    #[must_use]
+    fn(&self, x: &str) -> usize {
+        x.len()
+    },
+    #[must_use = "the parsed value is the only result"]
+    fn(self: Box<Self>, x: &str) -> usize {
+        x.len()
+    }
+}
+
+fn main() {
+    let parser = &Parser;
+    parser.parse("a");
+    Box::new(Parser).parse("b");
+}
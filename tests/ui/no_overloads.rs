@@ -0,0 +1,13 @@
+#![feature(unboxed_closures, fn_traits)]
+
+overloadable::overloadable! {
+    nothing as
+}
+
+pub struct Foo;
+
+overloadable::overloadable_member! {
+    Foo::nothing as
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+#![feature(unboxed_closures, fn_traits)]
+
+overloadable::overloadable! {
+    offset as
+    fn(#[default(0)] x: u8, y: u8) -> u8 {
+        x + y
+    }
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+#![feature(unboxed_closures, fn_traits)]
+
+overloadable::overloadable! {
+    widen as
+    #[coercible_to(fn(u16) -> u16)]
+    fn(x: u8) -> u16 {
+        x.into()
+    }
+}
+
+overloadable::overloadable! {
+    pick as
+    #[coercible_to(fn(u8, u8) -> u8)]
+    fn<T: Copy>(x: T, y: T) -> T {
+        if false { x } else { y }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+pub struct Meters(u32);
+
+overloadable::overloadable_op! {
+    + for Meters as
+    fn(&self, rhs: u32) -> Meters {
+        Meters(self.0 + rhs)
+    }
+}
+
+fn main() {}
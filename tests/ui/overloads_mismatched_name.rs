@@ -0,0 +1,23 @@
+#![feature(unboxed_closures, fn_traits)]
+
+#[overloadable::overloads]
+mod sizes {
+    pub fn size(x: u8) -> u8 {
+        x
+    }
+    pub fn length(x: &str) -> usize {
+        x.len()
+    }
+}
+
+#[overloadable::overloads]
+mod visibilities {
+    pub fn size(x: u8) -> u8 {
+        x
+    }
+    fn size(x: &str) -> usize {
+        x.len()
+    }
+}
+
+fn main() {}
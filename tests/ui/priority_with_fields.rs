@@ -0,0 +1,16 @@
+#![feature(unboxed_closures, fn_traits)]
+
+overloadable::overloadable! {
+    scaled { factor: u32 } as
+    #[overload_priority(1)]
+    fn(x: u32) -> u32 => x,
+    fn(x: u64) -> u64 => x,
+}
+
+overloadable::overloadable! {
+    out_of_range as
+    #[overload_priority(16)]
+    fn(x: u64) -> u64 => x,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+#![feature(unboxed_closures, fn_traits)]
+
+mod inner {
+    pub(crate) struct Wrapped(pub u8);
+
+    overloadable::overloadable! {
+        pub wrap as
+        fn(x: u8) -> Wrapped => Wrapped(x),
+    }
+}
+
+pub use inner::wrap;
+
+fn main() {
+    assert_eq!(wrap(1).0, 1);
+}
@@ -0,0 +1,10 @@
+#![feature(unboxed_closures, fn_traits)]
+
+overloadable::overloadable! {
+    only_one as
+    fn((x, 1): (u8, u8)) -> u8 {
+        x
+    }
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+#![feature(unboxed_closures, fn_traits)]
+
+overloadable::overloadable! {
+    make as
+    fn<T: Default>() -> T {
+        T::default()
+    }
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+#![feature(unboxed_closures, fn_traits)]
+
+overloadable::overloadable! {
+    widen as
+    fn(x: u8) -> u16 {
+        x as u16
+    },
+    fn(x: u8) -> u32 {
+        x as u32
+    }
+}
+
+fn main() {}
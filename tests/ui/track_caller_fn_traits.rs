@@ -0,0 +1,11 @@
+#![feature(unboxed_closures, fn_traits)]
+
+overloadable::overloadable! {
+    func as
+    #[track_caller]
+    fn(x: u8) -> u8 {
+        x
+    }
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+#![feature(unboxed_closures, fn_traits)]
+
+overloadable::overloadable! {
+    func as
+    fn<T>() {}
+}
+
+fn main() {}